@@ -0,0 +1,366 @@
+use bevy::ui::{FocusPolicy, RelativeCursorPosition};
+use serde::{Deserialize, Serialize};
+
+use crate::*;
+
+const AUDIO_SETTINGS_SAVE_KEY: &str = "audio_settings";
+
+const AUDIO_SETTINGS_KEY: KeyCode = KeyCode::V;
+
+const SLIDER_SIZE: Vec2 = Vec2::new(300.0, 24.0);
+const SLIDER_BACKGROUND_COLOR: Color = Color::rgb(0.15, 0.15, 0.15);
+const SLIDER_FILL_COLOR: Color = Color::rgb(0.0, 0.75, 0.85);
+const OVERLAY_BACKGROUND_COLOR: Color = Color::rgba(0.0, 0.0, 0.0, 0.85);
+
+pub struct AudioSettingsPlugin;
+
+impl Plugin for AudioSettingsPlugin {
+    fn build(&self, app: &mut App) {
+        app.insert_resource(
+            storage::load::<AudioSettings>(AUDIO_SETTINGS_SAVE_KEY).unwrap_or_default(),
+        )
+        .init_resource::<AudioSettingsOpen>()
+        .add_system(toggle_audio_settings)
+        .add_system(
+            show_audio_settings
+                .after(toggle_audio_settings)
+                .run_if(resource_changed::<AudioSettingsOpen>()),
+        )
+        .add_system(volume_slider_system)
+        .add_system(mute_button_system)
+        .add_system(close_audio_settings_button_system)
+        .add_system(
+            update_audio_settings_display
+                .after(volume_slider_system)
+                .after(mute_button_system),
+        );
+    }
+}
+
+/// How loud the game's audio should be
+#[derive(Resource, Serialize, Deserialize)]
+pub struct AudioSettings {
+    /// The volume of everything, from 0.0 to 1.0
+    pub master: f32,
+    /// The volume of music, from 0.0 to 1.0, before the master volume is applied
+    pub music: f32,
+    /// The volume of sound effects, from 0.0 to 1.0, before the master volume is applied
+    pub effects: f32,
+    /// Whether all audio is silenced
+    pub muted: bool,
+}
+
+impl Default for AudioSettings {
+    fn default() -> Self {
+        AudioSettings {
+            master: 0.5,
+            music: 1.0,
+            effects: 1.0,
+            muted: false,
+        }
+    }
+}
+
+impl AudioSettings {
+    /// Gets the volume to play music at, given how loud it would be with everything turned all the way up
+    pub fn music_volume(&self, volume: f32) -> f32 {
+        self.with_master_volume(volume * self.music)
+    }
+
+    /// Gets the volume to play a sound effect at, given how loud it would be with everything turned all the way up
+    pub fn effects_volume(&self, volume: f32) -> f32 {
+        self.with_master_volume(volume * self.effects)
+    }
+
+    /// Applies the master volume and mute to the provided volume
+    fn with_master_volume(&self, volume: f32) -> f32 {
+        if self.muted {
+            0.0
+        } else {
+            volume * self.master
+        }
+    }
+
+    /// Gets the volume of the provided channel
+    fn get(&self, channel: VolumeChannel) -> f32 {
+        match channel {
+            VolumeChannel::Master => self.master,
+            VolumeChannel::Music => self.music,
+            VolumeChannel::Effects => self.effects,
+        }
+    }
+
+    /// Changes the volume of the provided channel
+    fn set(&mut self, channel: VolumeChannel, volume: f32) {
+        match channel {
+            VolumeChannel::Master => self.master = volume,
+            VolumeChannel::Music => self.music = volume,
+            VolumeChannel::Effects => self.effects = volume,
+        }
+    }
+}
+
+/// Whether the audio settings are being shown
+#[derive(Resource, Default)]
+pub struct AudioSettingsOpen(pub bool);
+
+/// A volume that can be adjusted on its own
+#[derive(Clone, Copy, PartialEq, Eq)]
+enum VolumeChannel {
+    Master,
+    Music,
+    Effects,
+}
+
+impl VolumeChannel {
+    const ALL: [VolumeChannel; 3] = [
+        VolumeChannel::Master,
+        VolumeChannel::Music,
+        VolumeChannel::Effects,
+    ];
+
+    /// Gets the name of this channel
+    fn name(&self) -> &str {
+        match self {
+            VolumeChannel::Master => "master",
+            VolumeChannel::Music => "music",
+            VolumeChannel::Effects => "effects",
+        }
+    }
+}
+
+#[derive(Component)]
+struct AudioSettingsComponent;
+
+#[derive(Component)]
+struct VolumeSlider(VolumeChannel);
+
+#[derive(Component)]
+struct VolumeSliderFill(VolumeChannel);
+
+#[derive(Component)]
+struct VolumeText(VolumeChannel);
+
+#[derive(Component)]
+struct MuteButton;
+
+#[derive(Component)]
+struct CloseAudioSettingsButton;
+
+/// Opens or closes the audio settings when the audio settings key is pressed
+fn toggle_audio_settings(
+    keycode: Res<Input<KeyCode>>,
+    mut audio_settings_open: ResMut<AudioSettingsOpen>,
+) {
+    if keycode.just_pressed(AUDIO_SETTINGS_KEY) {
+        audio_settings_open.0 = !audio_settings_open.0;
+    }
+}
+
+/// Shows or hides the audio settings, saving them when they're closed
+fn show_audio_settings(
+    mut commands: Commands,
+    asset_server: Res<AssetServer>,
+    audio_settings: Res<AudioSettings>,
+    audio_settings_open: Res<AudioSettingsOpen>,
+    audio_settings_query: Query<Entity, With<AudioSettingsComponent>>,
+) {
+    if !audio_settings_open.0 {
+        if !audio_settings_query.is_empty() {
+            storage::save(AUDIO_SETTINGS_SAVE_KEY, &*audio_settings);
+        }
+        for entity in audio_settings_query.iter() {
+            commands.entity(entity).despawn_recursive();
+        }
+        return;
+    }
+
+    if !audio_settings_query.is_empty() {
+        return;
+    }
+
+    commands
+        .spawn(NodeBundle {
+            style: Style {
+                size: Size::new(Val::Percent(100.0), Val::Percent(100.0)),
+                position_type: PositionType::Absolute,
+                flex_direction: FlexDirection::Column,
+                justify_content: JustifyContent::Center,
+                align_items: AlignItems::Center,
+                ..default()
+            },
+            background_color: OVERLAY_BACKGROUND_COLOR.into(),
+            // keep clicks on the overlay from reaching buttons underneath it
+            focus_policy: FocusPolicy::Block,
+            z_index: ZIndex::Global(10),
+            ..default()
+        })
+        .insert(Interaction::default())
+        .insert(AudioSettingsComponent)
+        .with_children(|parent| {
+            parent.spawn(
+                TextBundle::from_section(
+                    "audio",
+                    TextStyle {
+                        font: asset_server.load(TITLE_FONT),
+                        font_size: 60.0,
+                        color: Color::rgb(0.0, 0.9, 1.0),
+                    },
+                )
+                .with_style(Style {
+                    margin: UiRect::bottom(Val::Px(20.0)),
+                    ..default()
+                }),
+            );
+
+            for channel in VolumeChannel::ALL {
+                let volume = audio_settings.get(channel);
+
+                parent
+                    .spawn(TextBundle::from_section(
+                        volume_text(channel, volume),
+                        TextStyle {
+                            font: asset_server.load(MONO_FONT),
+                            font_size: 26.0,
+                            color: Color::WHITE,
+                        },
+                    ))
+                    .insert(VolumeText(channel));
+
+                parent
+                    .spawn(NodeBundle {
+                        style: Style {
+                            size: Size::new(Val::Px(SLIDER_SIZE.x), Val::Px(SLIDER_SIZE.y)),
+                            margin: UiRect {
+                                top: Val::Px(5.0),
+                                bottom: Val::Px(20.0),
+                                ..default()
+                            },
+                            ..default()
+                        },
+                        background_color: SLIDER_BACKGROUND_COLOR.into(),
+                        focus_policy: FocusPolicy::Block,
+                        ..default()
+                    })
+                    .insert(Interaction::default())
+                    .insert(RelativeCursorPosition::default())
+                    .insert(VolumeSlider(channel))
+                    .with_children(|parent| {
+                        parent
+                            .spawn(NodeBundle {
+                                style: Style {
+                                    size: Size::new(
+                                        Val::Percent(volume * 100.0),
+                                        Val::Percent(100.0),
+                                    ),
+                                    ..default()
+                                },
+                                background_color: SLIDER_FILL_COLOR.into(),
+                                ..default()
+                            })
+                            .insert(VolumeSliderFill(channel));
+                    });
+            }
+
+            spawn_menu_button(parent, &asset_server, mute_button_text(&audio_settings))
+                .insert(MuteButton);
+
+            spawn_menu_button(parent, &asset_server, "done").insert(CloseAudioSettingsButton);
+        });
+}
+
+/// Builds the label for a volume slider
+fn volume_text(channel: VolumeChannel, volume: f32) -> String {
+    format!("{}: {:.0}%", channel.name(), volume * 100.0)
+}
+
+/// Builds the text for the mute button
+fn mute_button_text(audio_settings: &AudioSettings) -> &'static str {
+    if audio_settings.muted {
+        "unmute"
+    } else {
+        "mute"
+    }
+}
+
+/// Sets volumes based on where their sliders are clicked or dragged
+fn volume_slider_system(
+    mut audio_settings: ResMut<AudioSettings>,
+    slider_query: Query<(&Interaction, &RelativeCursorPosition, &VolumeSlider)>,
+) {
+    for (interaction, cursor_position, slider) in slider_query.iter() {
+        if *interaction != Interaction::Clicked {
+            continue;
+        }
+
+        let Some(cursor_position) = cursor_position.normalized else {
+            continue;
+        };
+
+        let volume = cursor_position.x.clamp(0.0, 1.0);
+        if audio_settings.get(slider.0) != volume {
+            audio_settings.set(slider.0, volume);
+        }
+    }
+}
+
+type InteractedMuteButtonTuple = (Changed<Interaction>, With<MuteButton>);
+
+/// Handles interactions with the mute button.
+fn mute_button_system(
+    mut audio_settings: ResMut<AudioSettings>,
+    interaction_query: Query<&Interaction, InteractedMuteButtonTuple>,
+) {
+    for interaction in interaction_query.iter() {
+        if *interaction == Interaction::Clicked {
+            audio_settings.muted = !audio_settings.muted;
+        }
+    }
+}
+
+type InteractedCloseAudioSettingsButtonTuple =
+    (Changed<Interaction>, With<CloseAudioSettingsButton>);
+
+/// Handles interactions with the button for closing the audio settings.
+fn close_audio_settings_button_system(
+    mut audio_settings_open: ResMut<AudioSettingsOpen>,
+    interaction_query: Query<&Interaction, InteractedCloseAudioSettingsButtonTuple>,
+) {
+    for interaction in interaction_query.iter() {
+        if *interaction == Interaction::Clicked {
+            audio_settings_open.0 = false;
+        }
+    }
+}
+
+/// Keeps the sliders and mute button in line with the audio settings
+fn update_audio_settings_display(
+    audio_settings: Res<AudioSettings>,
+    mut fill_query: Query<(&mut Style, &VolumeSliderFill)>,
+    mut volume_text_query: Query<(&mut Text, &VolumeText)>,
+    mute_button_query: Query<&Children, With<MuteButton>>,
+    mut button_text_query: Query<&mut Text, Without<VolumeText>>,
+) {
+    if !audio_settings.is_changed() {
+        return;
+    }
+
+    for (mut style, fill) in fill_query.iter_mut() {
+        style.size.width = Val::Percent(audio_settings.get(fill.0) * 100.0);
+    }
+
+    for (mut text, volume_text_channel) in volume_text_query.iter_mut() {
+        text.sections[0].value = volume_text(
+            volume_text_channel.0,
+            audio_settings.get(volume_text_channel.0),
+        );
+    }
+
+    for children in mute_button_query.iter() {
+        for child in children.iter() {
+            if let Ok(mut text) = button_text_query.get_mut(*child) {
+                text.sections[0].value = mute_button_text(&audio_settings).to_string();
+            }
+        }
+    }
+}
@@ -1,3 +1,7 @@
+use std::collections::HashMap;
+
+use serde::{Deserialize, Serialize};
+
 use crate::*;
 
 const PLAYER_PREVIEW_TRANSFORM: Transform =
@@ -5,31 +9,99 @@ const PLAYER_PREVIEW_TRANSFORM: Transform =
 
 const MENU_MUSIC_VOLUME: f32 = 0.25;
 
+const NEW_PERSONAL_BEST_COLOR: Color = Color::rgb(1.0, 0.9, 0.3);
+
+const HIGH_SCORES_SAVE_KEY: &str = "high_scores";
+const HIGH_SCORES_PER_LEVEL: usize = 5;
+
+const STAR_COLOR: Color = Color::rgb(1.0, 0.8, 0.2);
+const UNEARNED_STAR_COLOR: Color = Color::rgb(0.3, 0.3, 0.3);
+
+/// Sides that get unlocked once the player has earned enough stars in total across all campaign levels
+const STAR_UNLOCKS: [(usize, SideType); 2] =
+    [(8, SideType::ExtraPoints), (15, SideType::ExtremeBounce)];
+
 pub struct BetweenLevelsPlugin;
 
 impl Plugin for BetweenLevelsPlugin {
     fn build(&self, app: &mut App) {
-        app.add_system(
-            unlock_sides
-                .before(between_levels_setup)
-                .in_schedule(OnEnter(GameState::BetweenLevels)),
-        )
-        .add_system(between_levels_setup.in_schedule(OnEnter(GameState::BetweenLevels)))
-        .add_system(
-            despawn_components_system::<BetweenLevelsComponent>
-                .in_schedule(OnExit(GameState::BetweenLevels)),
-        )
-        .add_system(start_backround_music.in_schedule(OnEnter(GameState::BetweenLevels)))
-        .add_system(stop_background_music.in_schedule(OnExit(GameState::BetweenLevels)))
-        .add_system(side_selection_buttons_system.run_if(in_state(GameState::BetweenLevels)))
-        .add_system(next_level_button_system.run_if(in_state(GameState::BetweenLevels)))
-        .add_system(restart_level_button_system.run_if(in_state(GameState::BetweenLevels)));
+        app.insert_resource(storage::load::<HighScores>(HIGH_SCORES_SAVE_KEY).unwrap_or_default())
+            .insert_resource(EditedPlayer(Player::One))
+            .add_startup_system(unlock_reached_level_sides.before(unlock_earned_star_sides))
+            .add_startup_system(unlock_earned_star_sides)
+            .add_system(
+                record_high_score
+                    .before(unlock_sides)
+                    .in_schedule(OnEnter(GameState::BetweenLevels)),
+            )
+            .add_system(
+                unlock_sides
+                    .before(between_levels_setup)
+                    .in_schedule(OnEnter(GameState::BetweenLevels)),
+            )
+            .add_system(between_levels_setup.in_schedule(OnEnter(GameState::BetweenLevels)))
+            .add_system(
+                despawn_components_system::<BetweenLevelsComponent>
+                    .in_schedule(OnExit(GameState::BetweenLevels)),
+            )
+            .add_system(start_backround_music.in_schedule(OnEnter(GameState::BetweenLevels)))
+            .add_system(stop_background_music.in_schedule(OnExit(GameState::BetweenLevels)))
+            .add_system(update_background_music_volume.run_if(resource_changed::<AudioSettings>()))
+            .add_system(side_selection_buttons_system.run_if(in_state(GameState::BetweenLevels)))
+            .add_system(edited_player_button_system.run_if(in_state(GameState::BetweenLevels)))
+            .add_system(next_level_button_system.run_if(in_state(GameState::BetweenLevels)))
+            .add_system(restart_level_button_system.run_if(in_state(GameState::BetweenLevels)));
     }
 }
 
 #[derive(Resource)]
 struct MenuMusicController(Handle<AudioSink>);
 
+/// A score gotten on a campaign level
+#[derive(Clone, Serialize, Deserialize)]
+pub struct HighScore {
+    pub score: i32,
+    /// The number of days since the Unix epoch of the day the score was gotten
+    pub day: u64,
+    /// The types of the player's sides, in order of side ID
+    pub loadout: Vec<SideType>,
+    /// The seed the level was played with
+    pub seed: u64,
+}
+
+/// The best scores gotten on each campaign level, best first, by level ID
+#[derive(Resource, Default, Serialize, Deserialize)]
+pub struct HighScores(pub HashMap<usize, Vec<HighScore>>);
+
+impl HighScores {
+    /// Gets the best score gotten on the level with the provided ID, if it's been played
+    pub fn best(&self, level_id: usize) -> Option<i32> {
+        self.0
+            .get(&level_id)
+            .and_then(|high_scores| high_scores.first())
+            .map(|high_score| high_score.score)
+    }
+
+    /// Gets the total number of stars earned across all campaign levels
+    pub fn total_stars(&self) -> usize {
+        self.0
+            .keys()
+            .filter_map(|level_id| {
+                self.best(*level_id)
+                    .map(|best| LevelSettings::for_level(*level_id).stars(best))
+            })
+            .sum()
+    }
+}
+
+/// Where the score from the level that was just played ended up in its level's high scores, if it made it in at all
+#[derive(Resource)]
+struct NewHighScoreRank(Option<usize>);
+
+/// The sides that were unlocked after the level that was just played, along with the total stars needed for any that were unlocked by stars
+#[derive(Resource)]
+struct NewlyUnlockedSides(Vec<(SideType, Option<usize>)>);
+
 #[derive(Component)]
 struct BetweenLevelsComponent;
 
@@ -57,17 +129,158 @@ struct SideDescription(SideId);
 #[derive(Component)]
 struct PlayerPreview;
 
-/// Unlocks sides based on the completed level
+#[derive(Component)]
+struct SideCustomization;
+
+#[derive(Component)]
+struct EditedPlayerButton;
+
+#[derive(Component)]
+struct EditedPlayerButtonText;
+
+/// The player whose sides are being configured, when there are two players
+#[derive(Resource)]
+struct EditedPlayer(Player);
+
+impl EditedPlayer {
+    /// Gets the text to show on the button for switching which player's sides are being configured
+    fn button_text(&self) -> String {
+        format!("configuring {} (click to switch)", self.0.name())
+    }
+}
+
+/// Unlocks sides based on the completed level and the total stars earned
 fn unlock_sides(
+    mut commands: Commands,
     score: Res<Score>,
     level_settings: Res<LevelSettings>,
+    high_scores: Res<HighScores>,
     mut unlocked_sides: ResMut<UnlockedSides>,
+    two_players: Res<TwoPlayers>,
 ) {
+    let mut newly_unlocked_sides = Vec::new();
+
+    // unlocks only come from the solo campaign
+    if two_players.0 {
+        commands.insert_resource(NewlyUnlockedSides(newly_unlocked_sides));
+        return;
+    }
+
     if score.0 >= level_settings.min_score {
         for unlocked_side in &level_settings.sides_to_unlock {
-            unlocked_sides.0.push(*unlocked_side);
+            if !unlocked_sides.0.contains(unlocked_side) {
+                unlocked_sides.0.push(*unlocked_side);
+                newly_unlocked_sides.push((*unlocked_side, None));
+            }
+        }
+    }
+
+    let total_stars = high_scores.total_stars();
+    for (stars_needed, unlocked_side) in STAR_UNLOCKS {
+        if total_stars >= stars_needed && !unlocked_sides.0.contains(&unlocked_side) {
+            unlocked_sides.0.push(unlocked_side);
+            newly_unlocked_sides.push((unlocked_side, Some(stars_needed)));
         }
     }
+
+    commands.insert_resource(NewlyUnlockedSides(newly_unlocked_sides));
+}
+
+/// Unlocks sides for levels completed in previous sessions
+fn unlock_reached_level_sides(
+    progress: Res<CampaignProgress>,
+    mut unlocked_sides: ResMut<UnlockedSides>,
+) {
+    let mut level_settings = LevelSettings::first_level();
+    while level_settings.id < progress.furthest_level {
+        for unlocked_side in &level_settings.sides_to_unlock {
+            if !unlocked_sides.0.contains(unlocked_side) {
+                unlocked_sides.0.push(*unlocked_side);
+            }
+        }
+        level_settings = level_settings.next_level();
+    }
+}
+
+/// Unlocks sides for stars earned in previous sessions
+fn unlock_earned_star_sides(
+    high_scores: Res<HighScores>,
+    mut unlocked_sides: ResMut<UnlockedSides>,
+) {
+    let total_stars = high_scores.total_stars();
+    for (stars_needed, unlocked_side) in STAR_UNLOCKS {
+        if total_stars >= stars_needed && !unlocked_sides.0.contains(&unlocked_side) {
+            unlocked_sides.0.push(unlocked_side);
+        }
+    }
+}
+
+/// Builds text sections showing the provided number of stars out of the maximum for a level
+pub fn star_text_sections(
+    stars: usize,
+    asset_server: &AssetServer,
+    font_size: f32,
+) -> Vec<TextSection> {
+    (0..MAX_STARS_PER_LEVEL)
+        .map(|i| {
+            TextSection::new(
+                "*",
+                TextStyle {
+                    font: asset_server.load(TITLE_FONT),
+                    font_size,
+                    color: if i < stars {
+                        STAR_COLOR
+                    } else {
+                        UNEARNED_STAR_COLOR
+                    },
+                },
+            )
+        })
+        .collect()
+}
+
+/// Adds the score from the level that was just played to the level's high scores, if it's good enough
+#[allow(clippy::too_many_arguments)]
+fn record_high_score(
+    mut commands: Commands,
+    mut high_scores: ResMut<HighScores>,
+    score: Res<Score>,
+    level_settings: Res<LevelSettings>,
+    configured_sides: Res<ConfiguredSides>,
+    level_seed: Res<LevelSeed>,
+    game_mode: Res<GameMode>,
+    two_players: Res<TwoPlayers>,
+) {
+    // scores from two players sharing the arena aren't comparable to solo scores
+    if *game_mode != GameMode::Campaign || two_players.0 {
+        commands.insert_resource(NewHighScoreRank(None));
+        return;
+    }
+
+    let player_sides = level_settings.player_sides(&configured_sides);
+    let high_score = HighScore {
+        score: score.0,
+        day: current_day(),
+        loadout: (0..PLAYER_SHAPE_SIDES)
+            .map(|i| player_sides.get(&SideId(i)))
+            .collect(),
+        seed: level_seed.0,
+    };
+
+    let level_high_scores = high_scores.0.entry(level_settings.id).or_default();
+    // ties go to whoever got the score first
+    let rank = level_high_scores
+        .iter()
+        .position(|existing| existing.score < high_score.score)
+        .unwrap_or(level_high_scores.len());
+    if rank < HIGH_SCORES_PER_LEVEL {
+        level_high_scores.insert(rank, high_score);
+        level_high_scores.truncate(HIGH_SCORES_PER_LEVEL);
+        storage::save(HIGH_SCORES_SAVE_KEY, &*high_scores);
+        commands.insert_resource(NewHighScoreRank(Some(rank)));
+    } else {
+        commands.insert_resource(NewHighScoreRank(None));
+    }
 }
 
 /// Sets up the between levels screen
@@ -82,7 +295,19 @@ fn between_levels_setup(
     level_settings: Res<LevelSettings>,
     unlocked_sides: Res<UnlockedSides>,
     configured_sides: Res<ConfiguredSides>,
+    game_mode: Res<GameMode>,
+    time_attack_result: Option<Res<TimeAttackResult>>,
+    high_scores: Res<HighScores>,
+    new_high_score_rank: Res<NewHighScoreRank>,
+    newly_unlocked_sides: Res<NewlyUnlockedSides>,
+    two_players: Res<TwoPlayers>,
 ) {
+    commands.insert_resource(EditedPlayer(Player::One));
+
+    let time_attack_result = time_attack_result
+        .as_deref()
+        .filter(|_| *game_mode == GameMode::TimeAttack);
+
     // score text
     commands
         .spawn(NodeBundle {
@@ -104,7 +329,22 @@ fn between_levels_setup(
         .with_children(|parent| {
             parent.spawn(
                 TextBundle::from_section(
-                    format!("you got {} points on level {}", score.0, level_settings.id),
+                    match time_attack_result {
+                        Some(TimeAttackResult {
+                            time: Some(time), ..
+                        }) => format!(
+                            "you got {} points on level {} in {}",
+                            score.0,
+                            level_settings.id,
+                            format_precise_time(*time)
+                        ),
+                        Some(TimeAttackResult { time: None, .. }) => {
+                            format!("you ran out of time on level {}", level_settings.id)
+                        }
+                        None => {
+                            format!("you got {} points on level {}", score.0, level_settings.id)
+                        }
+                    },
                     TextStyle {
                         font: asset_server.load(MONO_FONT),
                         font_size: 45.0,
@@ -121,7 +361,150 @@ fn between_levels_setup(
                 }),
             );
 
-            if score.0 >= level_settings.min_score || level_settings.sides_to_unlock.is_empty() {
+            if *game_mode == GameMode::Campaign {
+                // stars
+                let stars = level_settings.stars(score.0);
+                parent.spawn(
+                    TextBundle::from_sections(star_text_sections(stars, &asset_server, 60.0))
+                        .with_text_alignment(TextAlignment::Center),
+                );
+
+                let next_star_text = match level_settings.star_scores.get(stars) {
+                    Some(star_score) => format!("next star at {star_score} points"),
+                    None => "all stars earned!".to_string(),
+                };
+                parent.spawn(
+                    TextBundle::from_section(
+                        format!(
+                            "{next_star_text} ({} stars in total)",
+                            high_scores.total_stars()
+                        ),
+                        TextStyle {
+                            font: asset_server.load(MAIN_FONT),
+                            font_size: 22.0,
+                            color: Color::rgb(0.8, 0.8, 0.8),
+                        },
+                    )
+                    .with_text_alignment(TextAlignment::Center)
+                    .with_style(Style {
+                        margin: UiRect {
+                            bottom: Val::Px(10.0),
+                            ..default()
+                        },
+                        ..default()
+                    }),
+                );
+            }
+
+            if new_high_score_rank.0 == Some(0) {
+                parent.spawn(
+                    TextBundle::from_section(
+                        "new personal best!",
+                        TextStyle {
+                            font: asset_server.load(MONO_FONT),
+                            font_size: 30.0,
+                            color: NEW_PERSONAL_BEST_COLOR,
+                        },
+                    )
+                    .with_text_alignment(TextAlignment::Center)
+                    .with_style(Style {
+                        margin: UiRect {
+                            bottom: Val::Px(10.0),
+                            ..default()
+                        },
+                        ..default()
+                    }),
+                );
+            }
+
+            if let Some(result) = time_attack_result {
+                // personal best text
+                let personal_best = match (result.time, result.previous_best) {
+                    (Some(time), Some(best)) if time < best => Some((
+                        format!(
+                            "new personal best! (previous best: {})",
+                            format_precise_time(best)
+                        ),
+                        NEW_PERSONAL_BEST_COLOR,
+                    )),
+                    (Some(_), None) => {
+                        Some(("new personal best!".to_string(), NEW_PERSONAL_BEST_COLOR))
+                    }
+                    (Some(time), Some(best)) => Some((
+                        format!(
+                            "personal best: {} (+{})",
+                            format_precise_time(best),
+                            format_precise_time(time - best)
+                        ),
+                        Color::rgb(0.8, 0.8, 0.8),
+                    )),
+                    (None, Some(best)) => Some((
+                        format!("personal best: {}", format_precise_time(best)),
+                        Color::rgb(0.8, 0.8, 0.8),
+                    )),
+                    (None, None) => None,
+                };
+
+                if let Some((text, color)) = personal_best {
+                    parent.spawn(
+                        TextBundle::from_section(
+                            text,
+                            TextStyle {
+                                font: asset_server.load(MONO_FONT),
+                                font_size: 30.0,
+                                color,
+                            },
+                        )
+                        .with_text_alignment(TextAlignment::Center)
+                        .with_style(Style {
+                            margin: UiRect {
+                                bottom: Val::Px(10.0),
+                                ..default()
+                            },
+                            ..default()
+                        }),
+                    );
+                }
+            }
+
+            let next_star_unlock = STAR_UNLOCKS
+                .iter()
+                .find(|(_, side_type)| !unlocked_sides.0.contains(side_type));
+            let unlocked_sides_texts = if !newly_unlocked_sides.0.is_empty() {
+                newly_unlocked_sides
+                    .0
+                    .iter()
+                    .map(|(side_type, stars_needed)| {
+                        let text = match stars_needed {
+                            Some(stars_needed) => format!(
+                                "new side unlocked: {} ({stars_needed} stars)",
+                                side_type.name().to_ascii_lowercase()
+                            ),
+                            None => format!(
+                                "new side unlocked: {}",
+                                side_type.name().to_ascii_lowercase()
+                            ),
+                        };
+                        (text, 35.0, Color::WHITE)
+                    })
+                    .collect()
+            } else if unlocked_sides.0.len() == SideType::ALL.len() {
+                vec![(
+                    "all sides have been unlocked".to_string(),
+                    25.0,
+                    Color::rgb(0.8, 0.8, 0.8),
+                )]
+            } else if let Some((stars_needed, _)) = next_star_unlock {
+                vec![(
+                    format!("earn {stars_needed} stars in total to unlock a new side"),
+                    25.0,
+                    Color::rgb(0.8, 0.8, 0.8),
+                )]
+            } else {
+                vec![]
+            };
+
+            if !unlocked_sides_texts.is_empty() {
                 // unlocked sides text
                 parent
                     .spawn(NodeBundle {
@@ -136,14 +519,14 @@ fn between_levels_setup(
                     })
                     .insert(BetweenLevelsComponent)
                     .with_children(|parent| {
-                        if level_settings.sides_to_unlock.is_empty() {
+                        for (text, font_size, color) in unlocked_sides_texts {
                             parent.spawn(
                                 TextBundle::from_section(
-                                    "all sides have been unlocked",
+                                    text,
                                     TextStyle {
                                         font: asset_server.load(MONO_FONT),
-                                        font_size: 25.0,
-                                        color: Color::rgb(0.8, 0.8, 0.8),
+                                        font_size,
+                                        color,
                                     },
                                 )
                                 .with_text_alignment(TextAlignment::Center)
@@ -152,31 +535,43 @@ fn between_levels_setup(
                                     ..default()
                                 }),
                             );
-                        } else {
-                            for unlocked_side in &level_settings.sides_to_unlock {
-                                parent.spawn(
-                                    TextBundle::from_section(
-                                        format!(
-                                            "new side unlocked: {}",
-                                            unlocked_side.name().to_ascii_lowercase()
-                                        ),
-                                        TextStyle {
-                                            font: asset_server.load(MONO_FONT),
-                                            font_size: 35.0,
-                                            color: Color::WHITE,
-                                        },
-                                    )
-                                    .with_text_alignment(TextAlignment::Center)
-                                    .with_style(Style {
-                                        margin: UiRect::all(Val::Auto),
-                                        ..default()
-                                    }),
-                                );
-                            }
                         }
                     });
             }
 
+            if two_players.0 {
+                // edited player button
+                parent
+                    .spawn(ButtonBundle {
+                        style: Style {
+                            size: Size::new(Val::Auto, Val::Auto),
+                            margin: UiRect {
+                                top: Val::Px(15.0),
+                                ..default()
+                            },
+                            padding: UiRect::all(Val::Px(5.0)),
+                            justify_content: JustifyContent::Center,
+                            align_items: AlignItems::Center,
+                            ..default()
+                        },
+                        background_color: NORMAL_BUTTON.into(),
+                        ..default()
+                    })
+                    .insert(EditedPlayerButton)
+                    .with_children(|parent| {
+                        parent
+                            .spawn(TextBundle::from_section(
+                                EditedPlayer(Player::One).button_text(),
+                                TextStyle {
+                                    font: asset_server.load(MONO_FONT),
+                                    font_size: 25.0,
+                                    color: NORMAL_BUTTON_TEXT_COLOR,
+                                },
+                            ))
+                            .insert(EditedPlayerButtonText);
+                    });
+            }
+
             // side customization
             parent
                 .spawn(NodeBundle {
@@ -194,6 +589,7 @@ fn between_levels_setup(
                     ..default()
                 })
                 .insert(BetweenLevelsComponent)
+                .insert(SideCustomization)
                 .with_children(|parent| {
                     for side in 0..PLAYER_SHAPE_SIDES {
                         spawn_side_customization_ui(
@@ -207,12 +603,94 @@ fn between_levels_setup(
                 });
         });
 
+    if *game_mode == GameMode::Campaign {
+        // high scores
+        commands
+            .spawn(NodeBundle {
+                style: Style {
+                    position_type: PositionType::Absolute,
+                    position: UiRect {
+                        left: Val::Px(10.0),
+                        bottom: Val::Px(10.0),
+                        ..default()
+                    },
+                    flex_direction: FlexDirection::Column,
+                    align_items: AlignItems::FlexStart,
+                    ..default()
+                },
+                ..default()
+            })
+            .insert(BetweenLevelsComponent)
+            .with_children(|parent| {
+                parent.spawn(TextBundle::from_section(
+                    format!("best scores on level {}", level_settings.id),
+                    TextStyle {
+                        font: asset_server.load(MONO_FONT),
+                        font_size: 25.0,
+                        color: Color::WHITE,
+                    },
+                ));
+
+                let level_high_scores = high_scores
+                    .0
+                    .get(&level_settings.id)
+                    .map(Vec::as_slice)
+                    .unwrap_or_default();
+                for (rank, high_score) in level_high_scores.iter().enumerate() {
+                    let (color, details_color) = if new_high_score_rank.0 == Some(rank) {
+                        (NEW_PERSONAL_BEST_COLOR, NEW_PERSONAL_BEST_COLOR)
+                    } else {
+                        (Color::rgb(0.9, 0.9, 0.9), Color::rgb(0.6, 0.6, 0.6))
+                    };
+
+                    parent.spawn(
+                        TextBundle::from_section(
+                            format!(
+                                "{}. {} points on {}",
+                                rank + 1,
+                                high_score.score,
+                                format_date(high_score.day)
+                            ),
+                            TextStyle {
+                                font: asset_server.load(MONO_FONT),
+                                font_size: 20.0,
+                                color,
+                            },
+                        )
+                        .with_style(Style {
+                            margin: UiRect {
+                                top: Val::Px(5.0),
+                                ..default()
+                            },
+                            ..default()
+                        }),
+                    );
+
+                    let loadout = high_score
+                        .loadout
+                        .iter()
+                        .map(|side_type| side_type.name())
+                        .collect::<Vec<_>>()
+                        .join(", ");
+                    parent.spawn(TextBundle::from_section(
+                        format!("{loadout} (seed {:x})", high_score.seed),
+                        TextStyle {
+                            font: asset_server.load(MAIN_FONT),
+                            font_size: 16.0,
+                            color: details_color,
+                        },
+                    ));
+                }
+            });
+    }
+
     // player preview
     spawn_player_shape(
         &mut commands,
         &mut meshes,
         &mut materials,
         &image_assets,
+        Player::One,
         &configured_sides,
         PLAYER_PREVIEW_TRANSFORM,
     )
@@ -336,9 +814,9 @@ fn between_levels_setup(
 fn spawn_side_customization_ui(
     side_id: SideId,
     root_parent: &mut ChildBuilder,
-    asset_server: &Res<AssetServer>,
-    unlocked_sides: &Res<UnlockedSides>,
-    configured_sides: &Res<ConfiguredSides>,
+    asset_server: &AssetServer,
+    unlocked_sides: &UnlockedSides,
+    configured_sides: &ConfiguredSides,
 ) {
     root_parent
         .spawn(NodeBundle {
@@ -535,13 +1013,20 @@ fn side_selection_buttons_system(
         Without<SideSelectionButtonText>,
     >,
     player_preview_query: Query<Entity, With<PlayerPreview>>,
-    mut configured_sides: ResMut<ConfiguredSides>,
+    mut player_one_configured_sides: ResMut<ConfiguredSides>,
+    mut player_two_configured_sides: ResMut<PlayerTwoConfiguredSides>,
+    edited_player: Res<EditedPlayer>,
     mut meshes: ResMut<Assets<Mesh>>,
     mut materials: ResMut<Assets<ColorMaterial>>,
     image_assets: Res<ImageAssets>,
 ) {
     let mut should_spawn_player_preview = false;
     for (interaction, interacted_button) in interacted_button_query.iter() {
+        let configured_sides = match edited_player.0 {
+            Player::One => &mut *player_one_configured_sides,
+            Player::Two => &mut player_two_configured_sides.0,
+        };
+
         if *interaction == Interaction::Clicked {
             configured_sides
                 .0
@@ -567,7 +1052,7 @@ fn side_selection_buttons_system(
 
             // update which buttons are disabled
             for (button_entity, button, mut background_color) in all_buttons_query.iter_mut() {
-                if can_side_be_selected(&button.side_type, &button.side_id, &configured_sides) {
+                if can_side_be_selected(&button.side_type, &button.side_id, configured_sides) {
                     commands.entity(button_entity).remove::<DisabledButton>();
                     *background_color = NORMAL_BUTTON.into();
                 } else {
@@ -581,7 +1066,7 @@ fn side_selection_buttons_system(
                 if can_side_be_selected(
                     &button_text.0.side_type,
                     &button_text.0.side_id,
-                    &configured_sides,
+                    configured_sides,
                 ) {
                     text.sections[0].style.color = NORMAL_BUTTON_TEXT_COLOR;
                 } else {
@@ -603,7 +1088,79 @@ fn side_selection_buttons_system(
                 &mut meshes,
                 &mut materials,
                 &image_assets,
-                &configured_sides,
+                edited_player.0,
+                configured_sides,
+                PLAYER_PREVIEW_TRANSFORM,
+            )
+            .insert(BetweenLevelsComponent)
+            .insert(PlayerPreview);
+        }
+    }
+}
+
+type InteractedEditedPlayerButtonTuple = (Changed<Interaction>, With<EditedPlayerButton>);
+
+/// Handles interactions with the button for switching which player's sides are being configured.
+#[allow(clippy::too_many_arguments)]
+fn edited_player_button_system(
+    mut commands: Commands,
+    interaction_query: Query<&Interaction, InteractedEditedPlayerButtonTuple>,
+    mut button_text_query: Query<&mut Text, With<EditedPlayerButtonText>>,
+    side_customization_query: Query<Entity, With<SideCustomization>>,
+    player_preview_query: Query<Entity, With<PlayerPreview>>,
+    mut edited_player: ResMut<EditedPlayer>,
+    player_one_configured_sides: Res<ConfiguredSides>,
+    player_two_configured_sides: Res<PlayerTwoConfiguredSides>,
+    unlocked_sides: Res<UnlockedSides>,
+    asset_server: Res<AssetServer>,
+    mut meshes: ResMut<Assets<Mesh>>,
+    mut materials: ResMut<Assets<ColorMaterial>>,
+    image_assets: Res<ImageAssets>,
+) {
+    for interaction in interaction_query.iter() {
+        if *interaction == Interaction::Clicked {
+            edited_player.0 = match edited_player.0 {
+                Player::One => Player::Two,
+                Player::Two => Player::One,
+            };
+            let configured_sides = match edited_player.0 {
+                Player::One => &*player_one_configured_sides,
+                Player::Two => &player_two_configured_sides.0,
+            };
+
+            for mut text in button_text_query.iter_mut() {
+                text.sections[0].value = edited_player.button_text();
+            }
+
+            // rebuild side customization for the newly edited player
+            for side_customization_entity in side_customization_query.iter() {
+                let mut side_customization = commands.entity(side_customization_entity);
+                side_customization.despawn_descendants();
+                side_customization.with_children(|parent| {
+                    for side in 0..PLAYER_SHAPE_SIDES {
+                        spawn_side_customization_ui(
+                            SideId(side),
+                            parent,
+                            &asset_server,
+                            &unlocked_sides,
+                            configured_sides,
+                        );
+                    }
+                });
+            }
+
+            // update player preview
+            for player_preview_entity in player_preview_query.iter() {
+                commands.entity(player_preview_entity).despawn_recursive();
+            }
+
+            spawn_player_shape(
+                &mut commands,
+                &mut meshes,
+                &mut materials,
+                &image_assets,
+                edited_player.0,
+                configured_sides,
                 PLAYER_PREVIEW_TRANSFORM,
             )
             .insert(BetweenLevelsComponent)
@@ -616,12 +1173,13 @@ fn side_selection_buttons_system(
 fn start_backround_music(
     mut commands: Commands,
     audio: Res<Audio>,
+    audio_settings: Res<AudioSettings>,
     audio_assets: Res<AudioAssets>,
     audio_sinks: Res<Assets<AudioSink>>,
 ) {
     let handle = audio_sinks.get_handle(audio.play_with_settings(
         audio_assets.menu_music.clone(),
-        PlaybackSettings::LOOP.with_volume(MENU_MUSIC_VOLUME * MASTER_VOLUME),
+        PlaybackSettings::LOOP.with_volume(audio_settings.music_volume(MENU_MUSIC_VOLUME)),
     ));
 
     commands.insert_resource(MenuMusicController(handle));
@@ -637,6 +1195,17 @@ fn stop_background_music(
     }
 }
 
+/// Keeps the volume of the background music in line with the audio settings
+fn update_background_music_volume(
+    music_controller: Option<Res<MenuMusicController>>,
+    audio_sinks: Res<Assets<AudioSink>>,
+    audio_settings: Res<AudioSettings>,
+) {
+    if let Some(sink) = music_controller.and_then(|controller| audio_sinks.get(&controller.0)) {
+        sink.set_volume(audio_settings.music_volume(MENU_MUSIC_VOLUME));
+    }
+}
+
 type InteractedNextLevelButtonTuple = (Changed<Interaction>, With<NextLevelButton>);
 
 /// Handles interactions with the next level button.
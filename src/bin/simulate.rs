@@ -0,0 +1,92 @@
+//! Simulates campaign levels and writes a CSV report of how they went.
+//!
+//! Usage: `simulate [--levels 1-5] [--runs 20] [--loadouts 5] [--controller bot|idle|spin] [--output report.csv]`
+
+use std::{fs::File, io, ops::RangeInclusive, process};
+
+use bevy_jam_03::simulation::{self, Controller, SimulationOptions};
+
+const DEFAULT_LEVELS: RangeInclusive<usize> = 1..=10;
+const DEFAULT_RUNS: u64 = 20;
+const DEFAULT_LOADOUTS: usize = 5;
+
+fn main() {
+    let mut options = SimulationOptions {
+        levels: DEFAULT_LEVELS.collect(),
+        runs: DEFAULT_RUNS,
+        loadouts: DEFAULT_LOADOUTS,
+        controller: Controller::Bot,
+    };
+    let mut output_path = None;
+
+    let mut args = std::env::args().skip(1);
+    while let Some(arg) = args.next() {
+        let Some(value) = args.next() else {
+            exit_with_usage(&format!("missing value for {arg}"));
+        };
+        match arg.as_str() {
+            "--levels" => {
+                options.levels = parse_levels(&value)
+                    .unwrap_or_else(|| exit_with_usage(&format!("invalid levels: {value}")))
+            }
+            "--runs" => {
+                options.runs = value
+                    .parse()
+                    .unwrap_or_else(|_| exit_with_usage(&format!("invalid runs: {value}")))
+            }
+            "--loadouts" => {
+                options.loadouts = value
+                    .parse()
+                    .unwrap_or_else(|_| exit_with_usage(&format!("invalid loadouts: {value}")))
+            }
+            "--controller" => {
+                options.controller = Controller::from_name(&value)
+                    .unwrap_or_else(|| exit_with_usage(&format!("invalid controller: {value}")))
+            }
+            "--output" => output_path = Some(value),
+            _ => exit_with_usage(&format!("unknown option: {arg}")),
+        }
+    }
+
+    let result = match output_path {
+        Some(path) => File::create(path).and_then(|mut file| simulation::run(&options, &mut file)),
+        None => simulation::run(&options, &mut io::stdout()),
+    };
+
+    if let Err(e) = result {
+        eprintln!("simulation failed: {e}");
+        process::exit(1);
+    }
+}
+
+/// Parses a comma-separated list of level IDs and ranges of level IDs, like `1-3,7`
+fn parse_levels(value: &str) -> Option<Vec<usize>> {
+    let mut levels = Vec::new();
+    for part in value.split(',') {
+        if let Some((start, end)) = part.split_once('-') {
+            levels.extend(start.parse::<usize>().ok()?..=end.parse().ok()?);
+        } else {
+            levels.push(part.parse().ok()?);
+        }
+    }
+
+    if levels.contains(&0) {
+        return None;
+    }
+
+    // the report has one set of rows per level, so each level only needs simulating once
+    let mut unique_levels = Vec::new();
+    for level in levels {
+        if !unique_levels.contains(&level) {
+            unique_levels.push(level);
+        }
+    }
+
+    Some(unique_levels)
+}
+
+fn exit_with_usage(message: &str) -> ! {
+    eprintln!("{message}");
+    eprintln!("usage: simulate [--levels 1-5] [--runs 20] [--loadouts 5] [--controller bot|idle|spin] [--output report.csv]");
+    process::exit(2);
+}
@@ -1,8 +1,12 @@
 use std::{collections::HashMap, ops::RangeInclusive, time::Duration};
 
 use bevy::{
-    ecs::{query::ReadOnlyWorldQuery, system::EntityCommands},
+    ecs::{
+        query::ReadOnlyWorldQuery,
+        system::{EntityCommands, SystemParam},
+    },
     input::mouse::MouseWheel,
+    render::{mesh::Indices, render_resource::PrimitiveTopology},
     sprite::{MaterialMesh2dBundle, Mesh2dHandle},
 };
 use bevy_asset_loader::prelude::*;
@@ -11,6 +15,8 @@ use bevy_tweening::Lerp;
 use instant::Instant;
 use iyes_progress::{ProgressCounter, ProgressPlugin};
 use rand::prelude::*;
+use rand_chacha::ChaCha8Rng;
+use serde::{Deserialize, Serialize};
 
 use crate::*;
 
@@ -19,25 +25,83 @@ const MOVE_RIGHT_KEY: KeyCode = KeyCode::D;
 const MOVE_UP_KEY: KeyCode = KeyCode::W;
 const MOVE_DOWN_KEY: KeyCode = KeyCode::S;
 
-const ROTATE_CLOCKWISE_KEY: KeyCode = KeyCode::Right;
+pub const ROTATE_CLOCKWISE_KEY: KeyCode = KeyCode::Right;
 const ROTATE_COUNTERCLOCKWISE_KEY: KeyCode = KeyCode::Left;
 
 const INCREASE_ROTATE_SENSITIVITY_KEY: KeyCode = KeyCode::Period;
 const DECREASE_ROTATE_SENSITIVITY_KEY: KeyCode = KeyCode::Comma;
 const COLOR_BLIND_MODE_KEY: KeyCode = KeyCode::M;
+/// Keys for starting each of the test levels from the menu in dev mode
+const TEST_LEVEL_KEYS: [KeyCode; 10] = [
+    KeyCode::Key1,
+    KeyCode::Key2,
+    KeyCode::Key3,
+    KeyCode::Key4,
+    KeyCode::Key5,
+    KeyCode::Key6,
+    KeyCode::Key7,
+    KeyCode::Key8,
+    KeyCode::Key9,
+    KeyCode::Key0,
+];
 
 const ROTATE_SENSITIVITY_ADJUST_AMOUNT: f32 = 0.2;
 
+const SOLO_KEY_BINDINGS: KeyBindings = KeyBindings {
+    move_left: MOVE_LEFT_KEY,
+    move_right: MOVE_RIGHT_KEY,
+    move_up: MOVE_UP_KEY,
+    move_down: MOVE_DOWN_KEY,
+    rotate_clockwise: ROTATE_CLOCKWISE_KEY,
+    rotate_counterclockwise: ROTATE_COUNTERCLOCKWISE_KEY,
+};
+const PLAYER_ONE_SPLIT_KEY_BINDINGS: KeyBindings = KeyBindings {
+    move_left: KeyCode::A,
+    move_right: KeyCode::D,
+    move_up: KeyCode::W,
+    move_down: KeyCode::S,
+    rotate_clockwise: KeyCode::E,
+    rotate_counterclockwise: KeyCode::Q,
+};
+const PLAYER_TWO_SPLIT_KEY_BINDINGS: KeyBindings = KeyBindings {
+    move_left: KeyCode::Left,
+    move_right: KeyCode::Right,
+    move_up: KeyCode::Up,
+    move_down: KeyCode::Down,
+    rotate_clockwise: KeyCode::L,
+    rotate_counterclockwise: KeyCode::K,
+};
+/// How far a gamepad stick has to be pushed before it does anything
+const GAMEPAD_DEADZONE: f32 = 0.2;
+
+/// How far ahead bots predict where balls will be, in seconds
+const BOT_LOOKAHEAD: f32 = 0.25;
+/// How far behind a ball bots line up before pushing it towards a score area
+const BOT_LINE_UP_DISTANCE: f32 = PLAYER_SHAPE_RADIUS * 2.0;
+/// How far from its target a bot starts slowing down
+const BOT_SLOW_DOWN_DISTANCE: f32 = 80.0;
+/// How much bots turn for each radian they're facing away from where they want to be facing
+const BOT_ROTATE_GAIN: f32 = 2.0;
+/// How close to facing the right way bots need to be before they push a ball, in radians
+const BOT_MAX_PUSH_ANGLE: f32 = 0.4;
+
 const MOVE_SPEED: f32 = 150000.0;
 const ROTATE_SPEED: f32 = 65.0;
 const SCROLL_ROTATE_SPEED: f32 = 3.0;
 
-pub const MASTER_VOLUME: f32 = 0.5;
-const HIT_SOUND_VOLUME: f32 = 0.4;
-const SPAWN_SOUND_VOLUME: f32 = 0.4;
-const GOOD_SCORE_VOLUME: f32 = 0.33;
-const BAD_SCORE_VOLUME: f32 = 0.4;
 const BG_MUSIC_VOLUME: f32 = 0.5;
+/// How loud the music is when nothing is happening, relative to how loud it is at full intensity
+const MUSIC_CALM_LEVEL: f32 = 0.6;
+/// How many balls need to be in play for the music to be at full intensity
+const MUSIC_FULL_INTENSITY_BALLS: f32 = 12.0;
+/// How much intensity is added to the music while the score is below the level's minimum score
+const MUSIC_BEHIND_INTENSITY: f32 = 0.25;
+/// How long before the end of a level the music crossfades into the tension layer
+const MUSIC_TENSION_TIME: Duration = Duration::from_secs(5);
+/// How much quieter the main track gets when the tension layer is fully faded in
+const MUSIC_TENSION_CROSSFADE: f32 = 0.4;
+/// How quickly music layers fade towards the levels they should be at, per second
+const MUSIC_FADE_SPEED: f32 = 0.75;
 
 const WALL_COLOR: Color = Color::Rgba {
     red: 0.2,
@@ -46,27 +110,105 @@ const WALL_COLOR: Color = Color::Rgba {
     alpha: 1.0,
 };
 
-const PLAY_AREA_RADIUS: f32 = WINDOW_HEIGHT / 2.0;
+pub const PLAY_AREA_RADIUS: f32 = WINDOW_HEIGHT / 2.0;
+const DEFAULT_ARENA: ArenaShape = ArenaShape::Rectangle {
+    width: PLAY_AREA_RADIUS * 2.0,
+    height: PLAY_AREA_RADIUS * 2.0,
+};
+const ARENA_CIRCLE_SEGMENTS: usize = 64;
+const WALL_THICKNESS: f32 = PLAY_AREA_RADIUS * 2.0;
 
 const SCORE_AREA_SIZE: f32 = 150.0;
 
 pub const PLAYER_SHAPE_SIDES: usize = 4;
 const PLAYER_SHAPE_RADIUS: f32 = 60.0;
 const PLAYER_COLLISION_GROUP: Group = Group::GROUP_1;
+const PLAYER_ONE_SHAPE_COLOR: Color = Color::rgba(1.0, 1.0, 1.0, 0.01);
+const PLAYER_TWO_SHAPE_COLOR: Color = Color::rgba(1.0, 0.5, 0.1, 0.15);
+/// How far from the center of the play area each player starts when there are two players
+const TWO_PLAYER_SPAWN_OFFSET: f32 = PLAYER_SHAPE_RADIUS * 2.5;
 
 const BALL_SIZE: f32 = 18.0;
 const EXTRA_POINT_BALL_SIZE: f32 = 25.0;
+const BALL_RESTITUTION: f32 = 1.0;
+const BALL_LINEAR_DAMPING: f32 = 0.0;
 const BALL_COLLISION_GROUP: Group = Group::GROUP_2;
 
+const OBSTACLE_COLLISION_GROUP: Group = Group::GROUP_3;
+const BUMPER_COLOR: Color = Color::rgb(0.9, 0.4, 0.9);
+const BUMPER_IMPULSE: f32 = 15.0;
+const ONE_WAY_BARRIER_COLOR: Color = Color::rgba(0.5, 0.8, 1.0, 0.5);
+const ONE_WAY_BARRIER_THICKNESS: f32 = 6.0;
+/// How far off from straight on (in radians) a ball can hit the solid side of a one-way barrier and still bounce off of it
+const ONE_WAY_BARRIER_ALLOWED_ANGLE: f32 = 1.5;
+
+const PORTAL_RADIUS: f32 = 35.0;
+const PORTAL_COLOR: Color = Color::rgba(0.6, 0.3, 1.0, 0.5);
+const PORTAL_DIRECTION_MARKER_SIZE: Vec2 = Vec2::new(14.0, 6.0);
+const PORTAL_COOLDOWN_DURATION: Duration = Duration::from_millis(500);
+
+const FORCE_FIELD_COLOR: Color = Color::rgba(0.6, 0.8, 1.0, 0.04);
+const FORCE_FIELD_MARKER_COLOR: Color = Color::rgba(0.6, 0.8, 1.0, 0.15);
+const FORCE_FIELD_MARKER_SIZE: Vec2 = Vec2::new(16.0, 3.0);
+const GRAVITY_WELL_RINGS: usize = 3;
+const VORTEX_SPOKES: usize = 6;
+/// How fast vortexes spin, in radians per second for each unit of strength
+const VORTEX_SPIN_SPEED: f32 = 0.005;
+const WIND_MARKER_SPACING: f32 = 60.0;
+
+const SPAWN_POINT_PREVIEW_COLOR: Color = Color::rgba(1.0, 1.0, 1.0, 0.15);
+const SPAWN_POINT_PREVIEW_MARKER_COLOR: Color = Color::rgba(1.0, 1.0, 1.0, 0.5);
+const SPAWN_POINT_PREVIEW_MARKER_SIZE: Vec2 = Vec2::new(40.0, 4.0);
+/// How small the area shown for a spawn point can be, so spawn points with no spread are still visible
+const SPAWN_POINT_PREVIEW_MIN_SIZE: f32 = 12.0;
+
 const FREEZE_DURATION: Duration = Duration::from_secs(3);
 const BOUNCE_BACKWARDS_VELOCITY: f32 = 100.0;
-const BOUNCE_BACKWARDS_DISTANCE: f32 = BALL_SIZE + 1.0;
+const BOUNCE_BACKWARDS_GAP: f32 = 1.0;
 const SCORE_AREA_RESIZE_DURATION: Duration = Duration::from_secs(5);
 const SCORE_AREA_RESIZE_AMOUNT: f32 = 40.0;
+const SCORE_AREA_GROW_FACTOR: f32 = (SCORE_AREA_SIZE + SCORE_AREA_RESIZE_AMOUNT) / SCORE_AREA_SIZE;
+const SCORE_AREA_SHRINK_FACTOR: f32 =
+    (SCORE_AREA_SIZE - SCORE_AREA_RESIZE_AMOUNT) / SCORE_AREA_SIZE;
+const SCORE_AREA_ARC_SEGMENTS: usize = 32;
 const DUPLICATE_COOLDOWN_DURATION: Duration = Duration::from_millis(1000);
 
+const CHAMELEON_CHANGE_INTERVAL: Duration = Duration::from_secs(4);
+const CHAMELEON_WARNING_DURATION: Duration = Duration::from_millis(1200);
+const CHAMELEON_FLASH_INTERVAL: Duration = Duration::from_millis(150);
+const CHAMELEON_FLASH_COLOR: Color = Color::WHITE;
+/// The stream chameleon balls draw their new types from, so they can be seeded from the level's seed without following the same sequence as the rest of the level
+const CHAMELEON_RNG_STREAM: u64 = 1;
+
 const TIMER_FONT_SIZE: f32 = 40.0;
 
+const SPLIT_FONT_SIZE: f32 = 24.0;
+const SPLIT_AHEAD_COLOR: Color = Color::rgb(0.4, 1.0, 0.4);
+const SPLIT_BEHIND_COLOR: Color = Color::rgb(1.0, 0.4, 0.4);
+
+const TIME_ATTACK_BESTS_SAVE_KEY: &str = "time_attack_bests";
+/// How many times longer than a level's normal duration players get to reach the target score in time attack mode
+const TIME_ATTACK_TIME_LIMIT_MULTIPLIER: u32 = 3;
+
+pub const MAX_STARS_PER_LEVEL: usize = 3;
+
+const DAILY_CHALLENGE_SCORES_SAVE_KEY: &str = "daily_challenge_scores";
+
+const VERSUS_DURATION: Duration = Duration::from_secs(90);
+const VERSUS_OWNER_LABEL_FONT_SIZE: f32 = 30.0;
+
+const ENDLESS_STARTING_LIVES: u32 = 5;
+/// How often endless mode gets harder
+const ENDLESS_RAMP_INTERVAL: Duration = Duration::from_secs(30);
+const ENDLESS_MAX_TIME_BETWEEN_GROUPS: Duration = Duration::from_secs(8);
+const ENDLESS_MIN_TIME_BETWEEN_GROUPS: Duration = Duration::from_secs(3);
+/// How long it takes for the time between groups to go from the max to the min in endless mode
+const ENDLESS_TIME_BETWEEN_GROUPS_RAMP_DURATION: Duration = Duration::from_secs(300);
+const ENDLESS_STARTING_BALLS_PER_GROUP: u32 = 3;
+const ENDLESS_MAX_BALLS_PER_GROUP: u32 = 8;
+const ENDLESS_CHAMELEON_BALL_CHANCE_INCREASE: f64 = 0.05;
+const ENDLESS_MAX_CHAMELEON_BALL_CHANCE: f64 = 0.3;
+
 const SCORE_AREA_HIT_ANIMATION_DURATION: Duration = Duration::from_millis(250);
 
 pub struct GamePlugin;
@@ -88,10 +230,13 @@ impl Plugin for GamePlugin {
         app.add_system(game_setup.in_schedule(OnEnter(GameState::Game)))
             .add_system(
                 despawn_components_system::<GameComponent>.in_schedule(OnExit(GameState::Game)),
-            );
+            )
+            .add_system(reset_view_size.in_schedule(OnExit(GameState::Game)))
+            .add_system(reset_gravity.in_schedule(OnExit(GameState::Game)));
 
         app.add_system(start_backround_music.in_schedule(OnEnter(GameState::Game)))
-            .add_system(stop_background_music.in_schedule(OnExit(GameState::Game)));
+            .add_system(stop_background_music.in_schedule(OnExit(GameState::Game)))
+            .add_system(update_music_intensity.run_if(in_state(GameState::Game)));
 
         app.insert_resource(UnlockedSides(
             [SideType::NothingSpecial, SideType::SpeedUp].into(),
@@ -109,7 +254,88 @@ impl Plugin for GamePlugin {
         .insert_resource(EntitiesToDespawn(Vec::new()))
         .insert_resource(RotateSensitivity(1.0))
         .insert_resource(ColorBlindMode(false))
-        .add_system(update_time_display.run_if(in_state(GameState::Game)))
+        .init_resource::<GameMode>()
+        .init_resource::<TwoPlayers>()
+        .init_resource::<BotPlayers>()
+        .insert_resource(PlayerTwoConfiguredSides(ConfiguredSides(
+            [
+                (SideId(0), SideType::NothingSpecial),
+                (SideId(1), SideType::SpeedUp),
+                (SideId(2), SideType::NothingSpecial),
+                (SideId(3), SideType::NothingSpecial),
+            ]
+            .into(),
+        )))
+        .insert_resource(
+            storage::load::<TimeAttackBests>(TIME_ATTACK_BESTS_SAVE_KEY).unwrap_or_default(),
+        )
+        .add_system(
+            update_time_attack_display
+                .run_if(in_state(GameState::Game))
+                .run_if(resource_equals(GameMode::TimeAttack)),
+        )
+        .add_system(
+            end_time_attack_level
+                .after(collisions)
+                .run_if(in_state(GameState::Game))
+                .run_if(resource_equals(GameMode::TimeAttack)),
+        )
+        .insert_resource(
+            storage::load::<DailyChallengeScores>(DAILY_CHALLENGE_SCORES_SAVE_KEY)
+                .unwrap_or_default(),
+        )
+        .add_system(
+            start_daily_challenge_attempt
+                .before(game_setup)
+                .in_schedule(OnEnter(GameState::Game))
+                .run_if(resource_equals(GameMode::DailyChallenge)),
+        )
+        .add_system(
+            end_daily_challenge
+                .after(collisions)
+                .run_if(in_state(GameState::Game))
+                .run_if(resource_equals(GameMode::DailyChallenge)),
+        )
+        .add_system(
+            update_time_display
+                .run_if(in_state(GameState::Game))
+                .run_if(counting_down),
+        )
+        .add_system(
+            update_versus_score_display
+                .after(collisions)
+                .run_if(in_state(GameState::Game))
+                .run_if(resource_equals(GameMode::Versus)),
+        )
+        .add_system(
+            end_versus_match
+                .after(collisions)
+                .run_if(in_state(GameState::Game))
+                .run_if(resource_equals(GameMode::Versus)),
+        )
+        .add_system(
+            update_survival_time_display
+                .run_if(in_state(GameState::Game))
+                .run_if(resource_equals(GameMode::Endless)),
+        )
+        .add_system(
+            update_lives_display
+                .after(collisions)
+                .run_if(in_state(GameState::Game))
+                .run_if(resource_equals(GameMode::Endless)),
+        )
+        .add_system(
+            ramp_up_endless_mode
+                .before(spawn_balls)
+                .run_if(in_state(GameState::Game))
+                .run_if(resource_equals(GameMode::Endless)),
+        )
+        .add_system(
+            end_endless_run
+                .after(collisions)
+                .run_if(in_state(GameState::Game))
+                .run_if(resource_equals(GameMode::Endless)),
+        )
         .add_system(spawn_balls.run_if(in_state(GameState::Game)))
         .add_system(toggle_color_blind_mode)
         .add_system(
@@ -123,6 +349,19 @@ impl Plugin for GamePlugin {
                 .run_if(in_state(GameState::Game)),
         )
         .add_system(player_movement.run_if(in_state(GameState::Game)))
+        .add_system(bot_movement.run_if(in_state(GameState::Game)))
+        .add_system(
+            end_demo
+                .after(collisions)
+                .run_if(in_state(GameState::Game))
+                .run_if(resource_equals(GameMode::Demo)),
+        )
+        .add_system(
+            end_editor_test
+                .after(collisions)
+                .run_if(in_state(GameState::Game))
+                .run_if(resource_equals(GameMode::EditorTest)),
+        )
         .add_system(collisions.run_if(in_state(GameState::Game)))
         .add_system(
             update_score_display
@@ -175,12 +414,36 @@ impl Plugin for GamePlugin {
                 .after(collisions)
                 .run_if(in_state(GameState::Game)),
         )
+        .add_system(
+            update_chameleon_balls
+                .after(collisions)
+                .run_if(in_state(GameState::Game)),
+        )
+        .add_system(
+            handle_bump_effect
+                .after(collisions)
+                .run_if(in_state(GameState::Game)),
+        )
+        .add_system(
+            handle_portal_effect
+                .after(collisions)
+                .run_if(in_state(GameState::Game)),
+        )
+        .add_system(
+            remove_portal_cooldown
+                .after(handle_portal_effect)
+                .run_if(in_state(GameState::Game)),
+        )
+        .add_system(move_score_areas.run_if(in_state(GameState::Game)))
+        .add_system(apply_force_fields.run_if(in_state(GameState::Game)))
+        .add_system(spin_vortexes.run_if(in_state(GameState::Game)))
         .add_system(unfreeze_entities.run_if(in_state(GameState::Game)))
         .add_system(unresize_entities.run_if(in_state(GameState::Game)))
         .add_system(
             end_level
                 .after(collisions)
-                .run_if(in_state(GameState::Game)),
+                .run_if(in_state(GameState::Game))
+                .run_if(resource_equals(GameMode::Campaign)),
         )
         .add_system(
             animate_score_area_hit
@@ -216,29 +479,32 @@ pub struct ImageAssets {
 #[derive(AssetCollection, Resource)]
 pub struct AudioAssets {
     #[asset(path = "sounds/hit.ogg")]
-    hit: Handle<AudioSource>,
+    pub hit: Handle<AudioSource>,
     #[asset(path = "sounds/up.ogg")]
-    up: Handle<AudioSource>,
-    #[asset(path = "sounds/up_more_2.ogg")]
-    up_more: Handle<AudioSource>,
+    pub up: Handle<AudioSource>,
+    #[asset(paths("sounds/up_more.ogg", "sounds/up_more_2.ogg"), collection(typed))]
+    pub up_more: Vec<Handle<AudioSource>>,
     #[asset(path = "sounds/down.ogg")]
-    down: Handle<AudioSource>,
+    pub down: Handle<AudioSource>,
     #[asset(path = "sounds/launch.ogg")]
-    launch: Handle<AudioSource>,
+    pub launch: Handle<AudioSource>,
     #[asset(path = "sounds/boop.ogg")]
-    boop: Handle<AudioSource>,
-    #[asset(path = "sounds/duplicate_2.ogg")]
-    duplicate: Handle<AudioSource>,
+    pub boop: Handle<AudioSource>,
+    #[asset(
+        paths("sounds/duplicate.ogg", "sounds/duplicate_2.ogg"),
+        collection(typed)
+    )]
+    pub duplicate: Vec<Handle<AudioSource>>,
     #[asset(path = "sounds/explode.ogg")]
-    explode: Handle<AudioSource>,
+    pub explode: Handle<AudioSource>,
     #[asset(path = "sounds/extra_points.ogg")]
-    extra_points: Handle<AudioSource>,
+    pub extra_points: Handle<AudioSource>,
     #[asset(path = "sounds/resize.ogg")]
-    resize: Handle<AudioSource>,
-    #[asset(path = "sounds/good_2.ogg")]
-    good: Handle<AudioSource>,
+    pub resize: Handle<AudioSource>,
+    #[asset(paths("sounds/good.ogg", "sounds/good_2.ogg"), collection(typed))]
+    pub good: Vec<Handle<AudioSource>>,
     #[asset(path = "sounds/bad.ogg")]
-    bad: Handle<AudioSource>,
+    pub bad: Handle<AudioSource>,
     #[asset(path = "sounds/choobcasher2.ogg")]
     game_music: Handle<AudioSource>,
     #[asset(path = "sounds/choobcasher.ogg")]
@@ -251,50 +517,535 @@ struct EntitiesToDespawn(Vec<Entity>);
 #[derive(Resource)]
 struct RotateSensitivity(f32);
 
-#[derive(Resource)]
+/// Whether a second player is playing alongside the first
+#[derive(Resource, Default)]
+pub struct TwoPlayers(pub bool);
+
+/// The players that are controlled by the built-in bot instead of a person
+#[derive(Resource, Default)]
+pub struct BotPlayers(pub Vec<Player>);
+
+/// Keys used to move and rotate a player shape
+struct KeyBindings {
+    move_left: KeyCode,
+    move_right: KeyCode,
+    move_up: KeyCode,
+    move_down: KeyCode,
+    rotate_clockwise: KeyCode,
+    rotate_counterclockwise: KeyCode,
+}
+
+impl KeyBindings {
+    /// Gets the direction of movement and rotation from the pressed keys. Positive rotation is counterclockwise.
+    fn input(&self, keycode: &Input<KeyCode>) -> (Vec2, f32) {
+        let mut movement = Vec2::ZERO;
+        if keycode.pressed(self.move_left) {
+            movement.x = -1.0;
+        } else if keycode.pressed(self.move_right) {
+            movement.x = 1.0;
+        }
+
+        if keycode.pressed(self.move_up) {
+            movement.y = 1.0;
+        } else if keycode.pressed(self.move_down) {
+            movement.y = -1.0;
+        }
+
+        let rotation = if keycode.pressed(self.rotate_clockwise) {
+            -1.0
+        } else if keycode.pressed(self.rotate_counterclockwise) {
+            1.0
+        } else {
+            0.0
+        };
+
+        (movement, rotation)
+    }
+}
+
+#[derive(Resource, Clone, Serialize, Deserialize)]
 pub struct LevelSettings {
     /// The ID of the level
     pub id: usize,
     /// Amount of time between spawning groups of balls
-    time_between_groups: Duration,
+    pub time_between_groups: Duration,
     /// Maximum amount of time before a new group gets spawned if there are no balls left on screen
-    max_respite_time: Duration,
+    pub max_respite_time: Duration,
     /// Amount of time between spawning balls in the same group
     time_between_spawns_in_group: Duration,
     /// Number of balls spawned per group
-    balls_per_group: u32,
-    /// Whether type B balls will spawn
-    type_b_active: bool,
-    /// Whether type D balls will spawn
-    type_d_active: bool,
+    pub balls_per_group: u32,
+    /// The score areas in the level, which also determine which types of balls will spawn
+    pub score_areas: Vec<ScoreAreaSettings>,
+    /// Obstacles placed inside the play area
+    pub obstacles: Vec<ObstacleSettings>,
+    /// Pairs of portals that teleport balls between each other
+    portals: Vec<PortalPairSettings>,
+    /// The acceleration applied to every ball at all times
+    gravity: Vec2,
+    /// Settings for fields that push balls around in part of the level
+    force_fields: Vec<ForceFieldSettings>,
+    /// How bouncy balls are when they hit each other, or `None` if balls pass through each other
+    ball_collision_restitution: Option<f32>,
+    /// Chance that a spawned ball will be a chameleon ball, from 0.0 to 1.0
+    chameleon_ball_chance: f64,
+    /// Physical properties of the balls
+    ball_properties: BallPropertiesSettings,
+    /// The shape of the play area
+    pub arena: ArenaShape,
     /// Settings for where to spawn balls
-    spawn_points: Vec<SpawnPoint>,
+    pub spawn_points: Vec<SpawnPoint>,
+    /// Seed for choosing which balls spawn where, or `None` to pick a new one each time the level is played
+    seed: Option<u64>,
+    /// Sides the player has to use on this level instead of the ones they configured
+    fixed_sides: Option<ConfiguredSides>,
     /// The time limit for the level
-    duration: Duration,
+    pub duration: Duration,
     /// The minimum score required to complete the level
     pub min_score: i32,
+    /// The scores required to earn one, two, and three stars on the level
+    pub star_scores: [i32; MAX_STARS_PER_LEVEL],
     /// The sides that will be unlocked when the level is completed
     pub sides_to_unlock: Vec<SideType>,
 }
 
 impl LevelSettings {
     /// Builds settings for the first level
-    fn first_level() -> LevelSettings {
+    pub fn first_level() -> LevelSettings {
         LevelSettings {
             id: 1,
             time_between_groups: Duration::from_secs(10),
             max_respite_time: Duration::from_secs(2),
             time_between_spawns_in_group: Duration::from_millis(500),
             balls_per_group: 3,
-            type_b_active: false,
-            type_d_active: false,
-            spawn_points: SpawnPoint::four_sides(5.0, 20.0),
+            score_areas: vec![
+                ScoreAreaSettings::top_left(BallType::A),
+                ScoreAreaSettings::bottom_right(BallType::C),
+            ],
+            obstacles: vec![],
+            portals: vec![],
+            gravity: Vec2::ZERO,
+            force_fields: vec![],
+            ball_collision_restitution: None,
+            chameleon_ball_chance: 0.0,
+            ball_properties: BallPropertiesSettings::default(),
+            arena: DEFAULT_ARENA,
+            spawn_points: SpawnPoint::four_sides(&DEFAULT_ARENA, 5.0, 20.0),
+            seed: None,
+            fixed_sides: None,
             duration: Duration::from_secs(32),
             sides_to_unlock: vec![SideType::FreezeOthers],
             min_score: 1,
+            star_scores: [1, 3, 5],
+        }
+    }
+
+    /// Builds settings for versus mode, where each player owns the two score areas on their side
+    pub fn versus() -> LevelSettings {
+        LevelSettings {
+            id: 0,
+            time_between_groups: Duration::from_secs(7),
+            max_respite_time: Duration::from_secs(1),
+            time_between_spawns_in_group: Duration::from_millis(500),
+            balls_per_group: 6,
+            score_areas: vec![
+                ScoreAreaSettings::top_left(BallType::A).owned_by(Player::One),
+                ScoreAreaSettings::bottom_left(BallType::D).owned_by(Player::One),
+                ScoreAreaSettings::top_right(BallType::B).owned_by(Player::Two),
+                ScoreAreaSettings::bottom_right(BallType::C).owned_by(Player::Two),
+            ],
+            obstacles: vec![],
+            portals: vec![],
+            gravity: Vec2::ZERO,
+            force_fields: vec![],
+            ball_collision_restitution: None,
+            chameleon_ball_chance: 0.0,
+            ball_properties: BallPropertiesSettings::default(),
+            arena: DEFAULT_ARENA,
+            spawn_points: vec![
+                SpawnPoint::top(&DEFAULT_ARENA, 5.0, 20.0),
+                SpawnPoint::bottom(&DEFAULT_ARENA, 5.0, 20.0),
+            ],
+            seed: None,
+            fixed_sides: None,
+            duration: VERSUS_DURATION,
+            sides_to_unlock: vec![],
+            min_score: 0,
+            star_scores: [0, 0, 0],
+        }
+    }
+
+    /// Builds settings for a new level in the level editor, which starts out the same as the first level
+    pub fn custom() -> LevelSettings {
+        LevelSettings {
+            id: 0,
+            sides_to_unlock: vec![],
+            ..LevelSettings::first_level()
+        }
+    }
+
+    /// Builds settings for the campaign level with the provided ID
+    pub fn for_level(id: usize) -> LevelSettings {
+        let mut level_settings = LevelSettings::first_level();
+        while level_settings.id < id {
+            level_settings = level_settings.next_level();
+        }
+
+        level_settings
+    }
+
+    /// Makes the level always use the provided seed
+    pub fn with_seed(mut self, seed: u64) -> LevelSettings {
+        self.seed = Some(seed);
+        self
+    }
+
+    /// Gets the number of stars earned by getting the provided score on the level
+    pub fn stars(&self, score: i32) -> usize {
+        self.star_scores
+            .iter()
+            .filter(|star_score| score >= **star_score)
+            .count()
+    }
+
+    /// Builds settings for endless mode. These get harder over time in `ramp_up_endless_mode`.
+    pub fn endless() -> LevelSettings {
+        LevelSettings {
+            id: 0,
+            time_between_groups: ENDLESS_MAX_TIME_BETWEEN_GROUPS,
+            max_respite_time: Duration::from_secs(2),
+            time_between_spawns_in_group: Duration::from_millis(500),
+            balls_per_group: ENDLESS_STARTING_BALLS_PER_GROUP,
+            score_areas: ScoreAreaSettings::endless()[..2].to_vec(),
+            obstacles: vec![],
+            portals: vec![],
+            gravity: Vec2::ZERO,
+            force_fields: vec![],
+            ball_collision_restitution: None,
+            chameleon_ball_chance: 0.0,
+            ball_properties: BallPropertiesSettings::default(),
+            arena: DEFAULT_ARENA,
+            spawn_points: SpawnPoint::four_sides(&DEFAULT_ARENA, 7.0, 30.0),
+            seed: None,
+            fixed_sides: None,
+            // endless mode isn't timed
+            duration: Duration::ZERO,
+            sides_to_unlock: vec![],
+            min_score: 0,
+            star_scores: [0, 0, 0],
+        }
+    }
+
+    /// Generates settings for the daily challenge on the provided day. The same day always gets the same settings, on every platform.
+    pub fn daily_challenge(day: u64) -> LevelSettings {
+        let mut rng = ChaCha8Rng::seed_from_u64(day);
+
+        let arena = [
+            DEFAULT_ARENA,
+            ArenaShape::Circle {
+                radius: PLAY_AREA_RADIUS,
+            },
+            ArenaShape::Rectangle {
+                width: 1000.0,
+                height: 620.0,
+            },
+        ]
+        .choose(&mut rng)
+        .expect("there should be arenas to choose from")
+        .clone();
+
+        // put a score area on the edge of the arena in each diagonal direction
+        let mut ball_types = [
+            BallType::A,
+            BallType::B,
+            BallType::C,
+            BallType::D,
+            BallType::E,
+            BallType::F,
+        ];
+        ball_types.shuffle(&mut rng);
+        let half_size = arena.size() / 2.0;
+        let score_areas = [
+            Vec2::new(-1.0, 1.0),
+            Vec2::new(1.0, 1.0),
+            Vec2::new(1.0, -1.0),
+            Vec2::new(-1.0, -1.0),
+        ]
+        .into_iter()
+        .zip(ball_types)
+        .map(|(corner, ball_type)| {
+            let direction = (corner * half_size).normalize();
+            ScoreAreaSettings::at(
+                ball_type,
+                direction * arena.distance_to_edge(Vec2::ZERO, direction),
+            )
+        })
+        .collect();
+
+        let obstacles = if rng.gen_bool(0.5) {
+            vec![
+                ObstacleSettings::bumper(Vec2::new(-190.0, 0.0), 30.0),
+                ObstacleSettings::bumper(Vec2::new(190.0, 0.0), 30.0),
+            ]
+        } else {
+            vec![]
+        };
+
+        let force_fields = if rng.gen_bool(0.3) {
+            vec![ForceFieldSettings::vortex(
+                Vec2::ZERO,
+                200.0,
+                *[-300.0, 300.0].choose(&mut rng).unwrap_or(&300.0),
+            )]
+        } else {
+            vec![]
+        };
+
+        let fixed_sides = SideType::ALL
+            .choose_multiple(&mut rng, PLAYER_SHAPE_SIDES)
+            .enumerate()
+            .map(|(i, side_type)| (SideId(i), *side_type))
+            .collect();
+
+        LevelSettings {
+            id: 0,
+            time_between_groups: Duration::from_secs(rng.gen_range(6..=9)),
+            max_respite_time: Duration::from_secs(1),
+            time_between_spawns_in_group: Duration::from_millis(500),
+            balls_per_group: rng.gen_range(4..=6),
+            score_areas,
+            obstacles,
+            portals: vec![],
+            gravity: Vec2::ZERO,
+            force_fields,
+            ball_collision_restitution: rng.gen_bool(0.25).then_some(0.9),
+            chameleon_ball_chance: *[0.0, 0.1, 0.2].choose(&mut rng).unwrap_or(&0.0),
+            ball_properties: BallPropertiesSettings::default(),
+            spawn_points: SpawnPoint::four_sides(&arena, 7.0, 30.0),
+            arena,
+            seed: Some(day),
+            fixed_sides: Some(ConfiguredSides(fixed_sides)),
+            duration: Duration::from_secs(64),
+            sides_to_unlock: vec![],
+            min_score: 0,
+            star_scores: [0, 0, 0],
+        }
+    }
+
+    /// Gets the sides the player uses on this level
+    pub fn player_sides<'a>(
+        &'a self,
+        configured_sides: &'a ConfiguredSides,
+    ) -> &'a ConfiguredSides {
+        self.fixed_sides.as_ref().unwrap_or(configured_sides)
+    }
+
+    /// Gets the types of balls that can spawn in this level
+    fn active_ball_types(&self) -> Vec<BallType> {
+        let mut ball_types = Vec::new();
+        for score_area in &self.score_areas {
+            if !ball_types.contains(&score_area.ball_type) {
+                ball_types.push(score_area.ball_type);
+            }
+        }
+
+        ball_types
+    }
+
+    /// Builds settings for a level that isn't part of the campaign, to try out level features on
+    fn test_level() -> LevelSettings {
+        LevelSettings {
+            id: 0,
+            time_between_groups: Duration::from_secs(7),
+            max_respite_time: Duration::from_secs(1),
+            balls_per_group: 5,
+            score_areas: ScoreAreaSettings::four_corners(),
+            spawn_points: SpawnPoint::four_sides(&DEFAULT_ARENA, 7.0, 30.0),
+            duration: Duration::from_secs(64),
+            sides_to_unlock: vec![],
+            ..LevelSettings::first_level()
         }
     }
 
+    /// Builds the test levels, which show off level features that the campaign doesn't use yet
+    fn test_levels() -> Vec<LevelSettings> {
+        vec![
+            // six ball types, with differently shaped score areas moving along the sides
+            LevelSettings {
+                score_areas: vec![
+                    ScoreAreaSettings::top_left(BallType::A),
+                    ScoreAreaSettings::top_right(BallType::B),
+                    ScoreAreaSettings::at(BallType::E, Vec2::new(PLAY_AREA_RADIUS, -80.0))
+                        .with_shape(ScoreAreaShape::Rectangle {
+                            width: 80.0,
+                            height: 260.0,
+                        })
+                        .with_movement(ScoreAreaMovement::Slide {
+                            to: Vec2::new(PLAY_AREA_RADIUS, 80.0),
+                            period: Duration::from_secs(6),
+                        }),
+                    ScoreAreaSettings::bottom_right(BallType::C),
+                    ScoreAreaSettings::bottom_left(BallType::D),
+                    ScoreAreaSettings::left(BallType::F)
+                        .with_shape(ScoreAreaShape::Arc {
+                            radius: 100.0,
+                            thickness: 50.0,
+                            start_angle: -90.0,
+                            end_angle: 90.0,
+                        })
+                        .with_movement(ScoreAreaMovement::Pulse {
+                            min_scale: 0.8,
+                            max_scale: 1.2,
+                            period: Duration::from_secs(3),
+                        }),
+                ],
+                spawn_points: vec![
+                    SpawnPoint::top(&DEFAULT_ARENA, 7.0, 30.0),
+                    SpawnPoint::bottom(&DEFAULT_ARENA, 7.0, 30.0),
+                ],
+                ..LevelSettings::test_level()
+            },
+            // score areas orbiting around the middle of the arena
+            LevelSettings {
+                score_areas: ScoreAreaSettings::four_corners()
+                    .into_iter()
+                    .map(|score_area| {
+                        score_area.with_movement(ScoreAreaMovement::Orbit {
+                            center: Vec2::ZERO,
+                            period: Duration::from_secs(30),
+                        })
+                    })
+                    .collect(),
+                ..LevelSettings::test_level()
+            },
+            // bumpers, a wall, and one-way barriers
+            LevelSettings {
+                obstacles: vec![
+                    ObstacleSettings::bumper(Vec2::new(-190.0, 0.0), 30.0),
+                    ObstacleSettings::bumper(Vec2::new(190.0, 0.0), 30.0),
+                    // only blocks balls, so the player can move through it
+                    ObstacleSettings::wall(Vec2::ZERO, 12.0, 120.0, 0.0).with_collision_groups(
+                        CollisionGroups::new(OBSTACLE_COLLISION_GROUP, BALL_COLLISION_GROUP),
+                    ),
+                    // let balls from the top and bottom walls into the middle, but not back out
+                    ObstacleSettings::one_way_barrier(Vec2::new(0.0, -170.0), 160.0, 0.0),
+                    ObstacleSettings::one_way_barrier(Vec2::new(0.0, 170.0), 160.0, 180.0),
+                ],
+                ..LevelSettings::test_level()
+            },
+            // a pair of portals in opposite corners
+            LevelSettings {
+                portals: vec![PortalPairSettings {
+                    positions: [Vec2::new(-200.0, 200.0), Vec2::new(200.0, -200.0)],
+                    rotations: [-45.0, 135.0],
+                }],
+                ..LevelSettings::test_level()
+            },
+            // a circular arena
+            {
+                let arena = ArenaShape::Circle {
+                    radius: PLAY_AREA_RADIUS,
+                };
+                LevelSettings {
+                    score_areas: [
+                        (BallType::A, 135.0_f32),
+                        (BallType::B, 45.0),
+                        (BallType::C, -45.0),
+                        (BallType::D, -135.0),
+                    ]
+                    .into_iter()
+                    .map(|(ball_type, angle)| {
+                        ScoreAreaSettings::at(
+                            ball_type,
+                            Vec2::from_angle(angle.to_radians()) * PLAY_AREA_RADIUS,
+                        )
+                        .with_shape(ScoreAreaShape::Circle { radius: 120.0 })
+                    })
+                    .collect(),
+                    spawn_points: SpawnPoint::four_sides(&arena, 7.0, 30.0),
+                    arena,
+                    ..LevelSettings::test_level()
+                }
+            },
+            // a hexagonal arena
+            {
+                let arena = ArenaShape::RegularPolygon {
+                    radius: 400.0,
+                    sides: 6,
+                };
+                // the top and bottom corners of the hexagon
+                let corner_x = 200.0;
+                let corner_y = 400.0 * (60.0_f32).to_radians().sin();
+                LevelSettings {
+                    score_areas: vec![
+                        ScoreAreaSettings::at(BallType::A, Vec2::new(-corner_x, corner_y)),
+                        ScoreAreaSettings::at(BallType::B, Vec2::new(corner_x, corner_y)),
+                        ScoreAreaSettings::at(BallType::C, Vec2::new(corner_x, -corner_y)),
+                        ScoreAreaSettings::at(BallType::D, Vec2::new(-corner_x, -corner_y)),
+                    ],
+                    spawn_points: vec![
+                        SpawnPoint::left(&arena, 7.0, 30.0),
+                        SpawnPoint::right(&arena, 7.0, 30.0),
+                    ],
+                    arena,
+                    ..LevelSettings::test_level()
+                }
+            },
+            // a wide rectangular arena
+            {
+                let arena = ArenaShape::Rectangle {
+                    width: 1000.0,
+                    height: 620.0,
+                };
+                LevelSettings {
+                    score_areas: vec![
+                        ScoreAreaSettings::at(BallType::A, Vec2::new(-500.0, 310.0)),
+                        ScoreAreaSettings::at(BallType::B, Vec2::new(500.0, 310.0)),
+                        ScoreAreaSettings::at(BallType::C, Vec2::new(500.0, -310.0)),
+                        ScoreAreaSettings::at(BallType::D, Vec2::new(-500.0, -310.0)),
+                    ],
+                    spawn_points: SpawnPoint::four_sides(&arena, 7.0, 30.0),
+                    arena,
+                    ..LevelSettings::test_level()
+                }
+            },
+            // gravity, with updrafts along the sides to help get balls back up to the top score areas
+            LevelSettings {
+                gravity: Vec2::new(0.0, -150.0),
+                force_fields: vec![
+                    ForceFieldSettings::wind(
+                        Vec2::new(-250.0, 0.0),
+                        Vec2::new(100.0, PLAY_AREA_RADIUS * 2.0),
+                        Vec2::new(0.0, 350.0),
+                    ),
+                    ForceFieldSettings::wind(
+                        Vec2::new(250.0, 0.0),
+                        Vec2::new(100.0, PLAY_AREA_RADIUS * 2.0),
+                        Vec2::new(0.0, 350.0),
+                    ),
+                ],
+                ..LevelSettings::test_level()
+            },
+            // a gravity well in the middle, with vortexes above and below it
+            LevelSettings {
+                force_fields: vec![
+                    ForceFieldSettings::gravity_well(Vec2::ZERO, 200.0, 400.0),
+                    ForceFieldSettings::vortex(Vec2::new(0.0, 240.0), 110.0, 300.0),
+                    ForceFieldSettings::vortex(Vec2::new(0.0, -240.0), 110.0, -300.0),
+                ],
+                ..LevelSettings::test_level()
+            },
+            // balls that bounce off each other, spawned in big groups for chain reactions
+            LevelSettings {
+                time_between_groups: Duration::from_secs(6),
+                time_between_spawns_in_group: Duration::from_millis(300),
+                balls_per_group: 7,
+                ball_collision_restitution: Some(0.9),
+                ..LevelSettings::test_level()
+            },
+        ]
+    }
+
     /// Builds settings for the level after this one
     pub fn next_level(&self) -> LevelSettings {
         match self.id {
@@ -304,12 +1055,26 @@ impl LevelSettings {
                 max_respite_time: Duration::from_secs(2),
                 time_between_spawns_in_group: Duration::from_millis(500),
                 balls_per_group: 3,
-                type_b_active: true,
-                type_d_active: false,
-                spawn_points: SpawnPoint::four_sides(5.0, 20.0),
+                score_areas: vec![
+                    ScoreAreaSettings::top_left(BallType::A),
+                    ScoreAreaSettings::top_right(BallType::B),
+                    ScoreAreaSettings::bottom_right(BallType::C),
+                ],
+                obstacles: vec![],
+                portals: vec![],
+                gravity: Vec2::ZERO,
+                force_fields: vec![],
+                ball_collision_restitution: None,
+                chameleon_ball_chance: 0.0,
+                ball_properties: BallPropertiesSettings::default(),
+                arena: DEFAULT_ARENA,
+                spawn_points: SpawnPoint::four_sides(&DEFAULT_ARENA, 5.0, 20.0),
+                seed: None,
+                fixed_sides: None,
                 duration: Duration::from_secs(40),
                 sides_to_unlock: vec![SideType::BounceBackwards],
                 min_score: 1,
+                star_scores: [1, 3, 5],
             },
             2 => LevelSettings {
                 id: 3,
@@ -317,12 +1082,22 @@ impl LevelSettings {
                 max_respite_time: Duration::from_secs(2),
                 time_between_spawns_in_group: Duration::from_millis(500),
                 balls_per_group: 3,
-                type_b_active: true,
-                type_d_active: true,
-                spawn_points: SpawnPoint::four_sides(5.0, 20.0),
+                score_areas: ScoreAreaSettings::four_corners(),
+                obstacles: vec![],
+                portals: vec![],
+                gravity: Vec2::ZERO,
+                force_fields: vec![],
+                ball_collision_restitution: None,
+                chameleon_ball_chance: 0.0,
+                ball_properties: BallPropertiesSettings::default(),
+                arena: DEFAULT_ARENA,
+                spawn_points: SpawnPoint::four_sides(&DEFAULT_ARENA, 5.0, 20.0),
+                seed: None,
+                fixed_sides: None,
                 duration: Duration::from_secs(50),
                 sides_to_unlock: vec![SideType::ResizeScoreAreas],
                 min_score: 1,
+                star_scores: [1, 3, 5],
             },
             3 => LevelSettings {
                 id: 4,
@@ -330,12 +1105,22 @@ impl LevelSettings {
                 max_respite_time: Duration::from_secs(2),
                 time_between_spawns_in_group: Duration::from_millis(500),
                 balls_per_group: 4,
-                type_b_active: true,
-                type_d_active: true,
-                spawn_points: SpawnPoint::four_sides(5.0, 22.0),
+                score_areas: ScoreAreaSettings::four_corners(),
+                obstacles: vec![],
+                portals: vec![],
+                gravity: Vec2::ZERO,
+                force_fields: vec![],
+                ball_collision_restitution: None,
+                chameleon_ball_chance: 0.0,
+                ball_properties: BallPropertiesSettings::default(),
+                arena: DEFAULT_ARENA,
+                spawn_points: SpawnPoint::four_sides(&DEFAULT_ARENA, 5.0, 22.0),
+                seed: None,
+                fixed_sides: None,
                 duration: Duration::from_secs(64),
-                sides_to_unlock: vec![SideType::Destroy, SideType::ExtraPoints],
+                sides_to_unlock: vec![SideType::Destroy],
                 min_score: 3,
+                star_scores: [3, 6, 9],
             },
             4 => LevelSettings {
                 id: 5,
@@ -343,12 +1128,22 @@ impl LevelSettings {
                 max_respite_time: Duration::from_secs(2),
                 time_between_spawns_in_group: Duration::from_millis(500),
                 balls_per_group: 4,
-                type_b_active: true,
-                type_d_active: true,
-                spawn_points: SpawnPoint::four_sides(5.0, 25.0),
+                score_areas: ScoreAreaSettings::four_corners(),
+                obstacles: vec![],
+                portals: vec![],
+                gravity: Vec2::ZERO,
+                force_fields: vec![],
+                ball_collision_restitution: None,
+                chameleon_ball_chance: 0.0,
+                ball_properties: BallPropertiesSettings::default(),
+                arena: DEFAULT_ARENA,
+                spawn_points: SpawnPoint::four_sides(&DEFAULT_ARENA, 5.0, 25.0),
+                seed: None,
+                fixed_sides: None,
                 duration: Duration::from_secs(64),
-                sides_to_unlock: vec![SideType::Duplicate, SideType::ExtremeBounce],
+                sides_to_unlock: vec![SideType::Duplicate],
                 min_score: 5,
+                star_scores: [5, 9, 13],
             },
             5 => LevelSettings {
                 id: 6,
@@ -356,12 +1151,22 @@ impl LevelSettings {
                 max_respite_time: Duration::from_secs(1),
                 time_between_spawns_in_group: Duration::from_millis(500),
                 balls_per_group: 5,
-                type_b_active: true,
-                type_d_active: true,
-                spawn_points: SpawnPoint::four_sides(6.0, 27.0),
+                score_areas: ScoreAreaSettings::four_corners(),
+                obstacles: vec![],
+                portals: vec![],
+                gravity: Vec2::ZERO,
+                force_fields: vec![],
+                ball_collision_restitution: None,
+                chameleon_ball_chance: 0.1,
+                ball_properties: BallPropertiesSettings::default(),
+                arena: DEFAULT_ARENA,
+                spawn_points: SpawnPoint::four_sides(&DEFAULT_ARENA, 6.0, 27.0),
+                seed: None,
+                fixed_sides: None,
                 duration: Duration::from_secs(64),
                 sides_to_unlock: vec![],
                 min_score: 7,
+                star_scores: [7, 12, 17],
             },
             6 => LevelSettings {
                 id: 7,
@@ -369,12 +1174,45 @@ impl LevelSettings {
                 max_respite_time: Duration::from_secs(1),
                 time_between_spawns_in_group: Duration::from_millis(500),
                 balls_per_group: 5,
-                type_b_active: true,
-                type_d_active: true,
-                spawn_points: SpawnPoint::four_sides(7.0, 30.0),
+                score_areas: ScoreAreaSettings::four_corners(),
+                obstacles: vec![],
+                portals: vec![],
+                gravity: Vec2::ZERO,
+                force_fields: vec![],
+                ball_collision_restitution: None,
+                chameleon_ball_chance: 0.2,
+                ball_properties: BallPropertiesSettings {
+                    by_type: [
+                        (
+                            BallType::B,
+                            BallProperties {
+                                radius: 14.0,
+                                mass: 0.05,
+                                restitution: 1.0,
+                                linear_damping: 0.0,
+                            },
+                        ),
+                        (
+                            BallType::D,
+                            BallProperties {
+                                radius: 24.0,
+                                mass: 0.3,
+                                restitution: 0.9,
+                                linear_damping: 0.3,
+                            },
+                        ),
+                    ]
+                    .into(),
+                    ..default()
+                },
+                arena: DEFAULT_ARENA,
+                spawn_points: SpawnPoint::four_sides(&DEFAULT_ARENA, 7.0, 30.0),
+                seed: None,
+                fixed_sides: None,
                 duration: Duration::from_secs(64),
                 sides_to_unlock: vec![],
                 min_score: 10,
+                star_scores: [10, 17, 23],
             },
             _ => LevelSettings {
                 id: self.id + 1,
@@ -382,138 +1220,1055 @@ impl LevelSettings {
                 max_respite_time: self.max_respite_time,
                 time_between_spawns_in_group: self.time_between_spawns_in_group,
                 balls_per_group: self.balls_per_group + 1,
-                type_b_active: true,
-                type_d_active: true,
+                score_areas: self.score_areas.clone(),
+                obstacles: self.obstacles.clone(),
+                portals: self.portals.clone(),
+                gravity: self.gravity,
+                force_fields: self.force_fields.clone(),
+                ball_collision_restitution: self.ball_collision_restitution,
+                chameleon_ball_chance: self.chameleon_ball_chance,
+                ball_properties: self.ball_properties.clone(),
+                arena: self.arena.clone(),
                 spawn_points: self.spawn_points.clone(),
+                seed: None,
+                fixed_sides: None,
                 duration: self.duration,
                 sides_to_unlock: vec![],
                 min_score: self.min_score + 3,
+                star_scores: self.star_scores.map(|score| score + 3),
             },
         }
     }
 }
 
-#[derive(Clone)]
-struct SpawnPoint {
+#[derive(Clone, Serialize, Deserialize)]
+pub struct SpawnPoint {
     /// Range of possible X coordinates
-    start_position_range_x: RangeInclusive<f32>,
+    pub start_position_range_x: RangeInclusive<f32>,
     /// Range of possible Y coordinates
-    start_position_range_y: RangeInclusive<f32>,
+    pub start_position_range_y: RangeInclusive<f32>,
     /// The range of possible initial impulses in the X direction on spawned balls
-    start_impulse_range_x: RangeInclusive<f32>,
+    pub start_impulse_range_x: RangeInclusive<f32>,
     /// The range of possible initial impulses in the Y direction on spawned balls
-    start_impulse_range_y: RangeInclusive<f32>,
+    pub start_impulse_range_y: RangeInclusive<f32>,
 }
 
 impl SpawnPoint {
+    /// Gets the middle of the area balls can spawn in
+    pub fn center(&self) -> Vec2 {
+        Vec2::new(
+            (self.start_position_range_x.start() + self.start_position_range_x.end()) / 2.0,
+            (self.start_position_range_y.start() + self.start_position_range_y.end()) / 2.0,
+        )
+    }
+
+    /// Gets the middle of the range of initial impulses on spawned balls
+    fn average_impulse(&self) -> Vec2 {
+        Vec2::new(
+            (self.start_impulse_range_x.start() + self.start_impulse_range_x.end()) / 2.0,
+            (self.start_impulse_range_y.start() + self.start_impulse_range_y.end()) / 2.0,
+        )
+    }
+
     /// Builds a spawn point next to the top wall
-    fn top(min_impulse: f32, max_impulse: f32) -> SpawnPoint {
+    fn top(arena: &ArenaShape, min_impulse: f32, max_impulse: f32) -> SpawnPoint {
+        let spread = arena.size().x / 6.0;
+        let y = SpawnPoint::distance_from_center(arena, Vec2::Y, spread);
         SpawnPoint {
-            start_position_range_x: (-PLAY_AREA_RADIUS / 3.0)..=(PLAY_AREA_RADIUS / 3.0),
-            start_position_range_y: (PLAY_AREA_RADIUS - BALL_SIZE - 1.0)
-                ..=(PLAY_AREA_RADIUS - BALL_SIZE - 1.0),
+            start_position_range_x: -spread..=spread,
+            start_position_range_y: y..=y,
             start_impulse_range_x: -10.0..=10.0,
             start_impulse_range_y: -max_impulse..=-min_impulse,
         }
     }
 
-    /// Builds a spawn point next to the top wall
-    fn bottom(min_impulse: f32, max_impulse: f32) -> SpawnPoint {
+    /// Builds a spawn point next to the bottom wall
+    fn bottom(arena: &ArenaShape, min_impulse: f32, max_impulse: f32) -> SpawnPoint {
+        let spread = arena.size().x / 6.0;
+        let y = -SpawnPoint::distance_from_center(arena, Vec2::NEG_Y, spread);
         SpawnPoint {
-            start_position_range_x: (-PLAY_AREA_RADIUS / 3.0)..=(PLAY_AREA_RADIUS / 3.0),
-            start_position_range_y: (-PLAY_AREA_RADIUS + BALL_SIZE + 1.0)
-                ..=(-PLAY_AREA_RADIUS + BALL_SIZE + 1.0),
+            start_position_range_x: -spread..=spread,
+            start_position_range_y: y..=y,
             start_impulse_range_x: -10.0..=10.0,
             start_impulse_range_y: min_impulse..=max_impulse,
         }
     }
 
     /// Builds a spawn point next to the left wall
-    fn left(min_impulse: f32, max_impulse: f32) -> SpawnPoint {
+    fn left(arena: &ArenaShape, min_impulse: f32, max_impulse: f32) -> SpawnPoint {
+        let spread = arena.size().y / 6.0;
+        let x = -SpawnPoint::distance_from_center(arena, Vec2::NEG_X, spread);
         SpawnPoint {
-            start_position_range_x: (-PLAY_AREA_RADIUS + BALL_SIZE + 1.0)
-                ..=(-PLAY_AREA_RADIUS + BALL_SIZE + 1.0),
-            start_position_range_y: (-PLAY_AREA_RADIUS / 3.0)..=(PLAY_AREA_RADIUS / 3.0),
+            start_position_range_x: x..=x,
+            start_position_range_y: -spread..=spread,
             start_impulse_range_x: min_impulse..=max_impulse,
             start_impulse_range_y: -10.0..=10.0,
         }
     }
 
     /// Builds a spawn point next to the right wall
-    fn right(min_impulse: f32, max_impulse: f32) -> SpawnPoint {
+    fn right(arena: &ArenaShape, min_impulse: f32, max_impulse: f32) -> SpawnPoint {
+        let spread = arena.size().y / 6.0;
+        let x = SpawnPoint::distance_from_center(arena, Vec2::X, spread);
         SpawnPoint {
-            start_position_range_x: (PLAY_AREA_RADIUS - BALL_SIZE - 1.0)
-                ..=(PLAY_AREA_RADIUS - BALL_SIZE - 1.0),
-            start_position_range_y: (-PLAY_AREA_RADIUS / 3.0)..=(PLAY_AREA_RADIUS / 3.0),
+            start_position_range_x: x..=x,
+            start_position_range_y: -spread..=spread,
             start_impulse_range_x: -max_impulse..=-min_impulse,
             start_impulse_range_y: -10.0..=10.0,
         }
     }
 
     /// Builds spawn points next to each wall
-    fn four_sides(min_impulse: f32, max_impulse: f32) -> Vec<SpawnPoint> {
+    fn four_sides(arena: &ArenaShape, min_impulse: f32, max_impulse: f32) -> Vec<SpawnPoint> {
         vec![
-            SpawnPoint::top(min_impulse, max_impulse),
-            SpawnPoint::bottom(min_impulse, max_impulse),
-            SpawnPoint::left(min_impulse, max_impulse),
-            SpawnPoint::right(min_impulse, max_impulse),
+            SpawnPoint::top(arena, min_impulse, max_impulse),
+            SpawnPoint::bottom(arena, min_impulse, max_impulse),
+            SpawnPoint::left(arena, min_impulse, max_impulse),
+            SpawnPoint::right(arena, min_impulse, max_impulse),
         ]
     }
+
+    /// Determines how far from the center balls can spawn in the provided direction without touching a wall, when they can be up to `spread` off to either side
+    fn distance_from_center(arena: &ArenaShape, direction: Vec2, spread: f32) -> f32 {
+        let to_side = direction.perp() * spread;
+        let distance_to_wall = arena
+            .distance_to_edge(to_side, direction)
+            .min(arena.distance_to_edge(-to_side, direction));
+
+        distance_to_wall - BALL_SIZE - 1.0
+    }
 }
 
-#[derive(Resource)]
-pub struct UnlockedSides(pub Vec<SideType>);
+#[derive(Clone, Serialize, Deserialize)]
+pub enum ArenaShape {
+    /// A rectangle with the provided width and height
+    Rectangle { width: f32, height: f32 },
+    /// A circle with the provided radius
+    Circle { radius: f32 },
+    /// A regular polygon with the provided number of sides, with its corners the provided distance from the center and one corner pointing right
+    RegularPolygon { radius: f32, sides: usize },
+}
 
-#[derive(Resource)]
-pub struct ConfiguredSides(pub HashMap<SideId, SideType>);
+impl ArenaShape {
+    /// Gets the corners of the arena, going counterclockwise. Circles are approximated with lots of corners.
+    fn corners(&self) -> Vec<Vec2> {
+        let regular_polygon_corners = |radius: f32, sides: usize| {
+            (0..sides)
+                .map(|i| Vec2::from_angle(std::f32::consts::TAU * i as f32 / sides as f32) * radius)
+                .collect()
+        };
 
-impl ConfiguredSides {
-    /// Gets the type of the side with the provided ID. Panics if the side is not configured.
-    pub fn get(&self, side_id: &SideId) -> SideType {
-        *self
-            .0
-            .get(side_id)
-            .unwrap_or_else(|| panic!("side {side_id:?} should be configured"))
+        match self {
+            ArenaShape::Rectangle { width, height } => {
+                let (x, y) = (width / 2.0, height / 2.0);
+                vec![
+                    Vec2::new(x, -y),
+                    Vec2::new(x, y),
+                    Vec2::new(-x, y),
+                    Vec2::new(-x, -y),
+                ]
+            }
+            ArenaShape::Circle { radius } => {
+                regular_polygon_corners(*radius, ARENA_CIRCLE_SEGMENTS)
+            }
+            ArenaShape::RegularPolygon { radius, sides } => {
+                regular_polygon_corners(*radius, *sides)
+            }
+        }
+    }
+
+    /// Gets the width and height of the smallest rectangle that contains the whole arena
+    pub fn size(&self) -> Vec2 {
+        let corners = self.corners();
+        let min = corners
+            .iter()
+            .copied()
+            .reduce(Vec2::min)
+            .unwrap_or_default();
+        let max = corners
+            .iter()
+            .copied()
+            .reduce(Vec2::max)
+            .unwrap_or_default();
+
+        max - min
+    }
+
+    /// Gets the size of the area that needs to be visible to see the whole arena along with the UI next to it
+    pub fn view_size(&self) -> Vec2 {
+        let ui_width = WINDOW_WIDTH - DEFAULT_ARENA.size().x;
+        (self.size() + Vec2::new(ui_width, 0.0)).max(Vec2::new(WINDOW_WIDTH, WINDOW_HEIGHT))
+    }
+
+    /// Determines how far it is from the provided point inside the arena to its edge, going in the provided direction
+    fn distance_to_edge(&self, from: Vec2, direction: Vec2) -> f32 {
+        let direction = direction.normalize_or_zero();
+        let corners = self.corners();
+        let mut distance = f32::INFINITY;
+        for (i, corner) in corners.iter().enumerate() {
+            let edge = corners[(i + 1) % corners.len()] - *corner;
+            let denominator = direction.perp_dot(edge);
+            if denominator.abs() <= f32::EPSILON {
+                // the edge is parallel to the direction
+                continue;
+            }
+
+            let to_corner = *corner - from;
+            let distance_along_direction = to_corner.perp_dot(edge) / denominator;
+            let fraction_along_edge = to_corner.perp_dot(direction) / denominator;
+            if distance_along_direction >= 0.0 && (0.0..=1.0).contains(&fraction_along_edge) {
+                distance = distance.min(distance_along_direction);
+            }
+        }
+
+        distance
+    }
+
+    /// Moves the provided point towards the center of the arena if it's not at least `margin` away from the edge
+    pub fn clamp_inside(&self, point: Vec2, margin: f32) -> Vec2 {
+        let direction = point.normalize_or_zero();
+        let max_distance = (self.distance_to_edge(Vec2::ZERO, direction) - margin).max(0.0);
+        if point.length() > max_distance {
+            direction * max_distance
+        } else {
+            point
+        }
     }
 }
 
-#[derive(Resource)]
-struct GameMusicController(Handle<AudioSink>);
+#[derive(Clone, Serialize, Deserialize)]
+pub struct ScoreAreaSettings {
+    /// The type of ball that scores points in this score area
+    pub ball_type: BallType,
+    /// Where the center of the score area is
+    pub position: Vec2,
+    /// The rotation of the score area, in degrees counterclockwise
+    rotation: f32,
+    /// The shape and size of the score area
+    shape: ScoreAreaShape,
+    /// How the score area moves over the course of the level, if at all
+    movement: Option<ScoreAreaMovement>,
+    /// The player that gets points from this score area in versus mode
+    owner: Option<Player>,
+}
 
-#[derive(Resource)]
-pub struct Score(pub i32);
+impl ScoreAreaSettings {
+    /// Builds a circular score area of the default size at the provided position
+    pub fn at(ball_type: BallType, position: Vec2) -> ScoreAreaSettings {
+        ScoreAreaSettings {
+            ball_type,
+            position,
+            rotation: 0.0,
+            shape: ScoreAreaShape::Circle {
+                radius: SCORE_AREA_SIZE,
+            },
+            movement: None,
+            owner: None,
+        }
+    }
 
-#[derive(Resource)]
-struct LevelEndTime(Instant);
+    /// Makes this score area belong to the provided player
+    fn owned_by(self, player: Player) -> ScoreAreaSettings {
+        ScoreAreaSettings {
+            owner: Some(player),
+            ..self
+        }
+    }
 
-#[derive(Resource)]
-struct ColorBlindMode(bool);
+    /// Replaces the shape of this score area with the provided one
+    fn with_shape(self, shape: ScoreAreaShape) -> ScoreAreaSettings {
+        ScoreAreaSettings { shape, ..self }
+    }
 
-#[derive(Component)]
-struct LoadingComponent;
+    /// Makes this score area move in the provided way
+    fn with_movement(self, movement: ScoreAreaMovement) -> ScoreAreaSettings {
+        ScoreAreaSettings {
+            movement: Some(movement),
+            ..self
+        }
+    }
 
-#[derive(Component)]
-struct LoadingText;
+    /// Builds a score area in the top left corner
+    fn top_left(ball_type: BallType) -> ScoreAreaSettings {
+        ScoreAreaSettings::at(ball_type, Vec2::new(-PLAY_AREA_RADIUS, PLAY_AREA_RADIUS))
+    }
 
-#[derive(Component)]
-struct GameComponent;
+    /// Builds a score area in the top right corner
+    fn top_right(ball_type: BallType) -> ScoreAreaSettings {
+        ScoreAreaSettings::at(ball_type, Vec2::new(PLAY_AREA_RADIUS, PLAY_AREA_RADIUS))
+    }
 
-#[derive(Component)]
-struct PlayerShape;
+    /// Builds a score area in the bottom right corner
+    fn bottom_right(ball_type: BallType) -> ScoreAreaSettings {
+        ScoreAreaSettings::at(ball_type, Vec2::new(PLAY_AREA_RADIUS, -PLAY_AREA_RADIUS))
+    }
 
-#[derive(Component, Eq, PartialEq, Hash, Clone, Copy, Debug)]
-pub struct SideId(pub usize);
+    /// Builds a score area in the bottom left corner
+    fn bottom_left(ball_type: BallType) -> ScoreAreaSettings {
+        ScoreAreaSettings::at(ball_type, Vec2::new(-PLAY_AREA_RADIUS, -PLAY_AREA_RADIUS))
+    }
 
-impl SideId {
-    /// Finds the ID of the opposite side
-    fn opposite_side(&self) -> SideId {
-        SideId((self.0 + (PLAYER_SHAPE_SIDES / 2)) % PLAYER_SHAPE_SIDES)
+    /// Builds a score area in the middle of the left wall
+    fn left(ball_type: BallType) -> ScoreAreaSettings {
+        ScoreAreaSettings::at(ball_type, Vec2::new(-PLAY_AREA_RADIUS, 0.0))
+    }
+
+    /// Builds the score areas used in endless mode, in the order they're added
+    fn endless() -> Vec<ScoreAreaSettings> {
+        vec![
+            ScoreAreaSettings::top_left(BallType::A),
+            ScoreAreaSettings::bottom_right(BallType::C),
+            ScoreAreaSettings::top_right(BallType::B),
+            ScoreAreaSettings::bottom_left(BallType::D),
+        ]
+    }
+
+    /// Builds score areas in each corner for ball types A through D, going clockwise from the top left
+    fn four_corners() -> Vec<ScoreAreaSettings> {
+        vec![
+            ScoreAreaSettings::top_left(BallType::A),
+            ScoreAreaSettings::top_right(BallType::B),
+            ScoreAreaSettings::bottom_right(BallType::C),
+            ScoreAreaSettings::bottom_left(BallType::D),
+        ]
     }
 }
 
-#[derive(Component, PartialEq, Eq, Hash, Clone, Copy)]
-pub enum SideType {
-    NothingSpecial,
+#[derive(Component, Clone, Serialize, Deserialize)]
+enum ScoreAreaShape {
+    /// A circle with the provided radius
+    Circle { radius: f32 },
+    /// A rectangle with the provided width and height
+    Rectangle { width: f32, height: f32 },
+    /// A curved band centered on a ring with the provided radius, spanning the provided angles (in degrees counterclockwise from the positive X axis)
+    Arc {
+        radius: f32,
+        thickness: f32,
+        start_angle: f32,
+        end_angle: f32,
+    },
+}
+
+impl ScoreAreaShape {
+    /// Builds a copy of this shape scaled by the provided factor
+    fn scaled(&self, factor: f32) -> ScoreAreaShape {
+        match self {
+            ScoreAreaShape::Circle { radius } => ScoreAreaShape::Circle {
+                radius: radius * factor,
+            },
+            ScoreAreaShape::Rectangle { width, height } => ScoreAreaShape::Rectangle {
+                width: width * factor,
+                height: height * factor,
+            },
+            ScoreAreaShape::Arc {
+                radius,
+                thickness,
+                start_angle,
+                end_angle,
+            } => ScoreAreaShape::Arc {
+                radius: radius * factor,
+                thickness: thickness * factor,
+                start_angle: *start_angle,
+                end_angle: *end_angle,
+            },
+        }
+    }
+
+    /// Builds a mesh in this shape
+    fn mesh(&self) -> Mesh {
+        match self {
+            ScoreAreaShape::Circle { radius } => shape::Circle::new(*radius).into(),
+            ScoreAreaShape::Rectangle { width, height } => {
+                shape::Quad::new(Vec2::new(*width, *height)).into()
+            }
+            ScoreAreaShape::Arc { .. } => {
+                let mut positions = Vec::new();
+                let mut indices = Vec::new();
+                for (i, corners) in self.arc_segments().iter().enumerate() {
+                    positions.extend(corners.iter().map(|corner| [corner.x, corner.y, 0.0]));
+                    let first = (i * 4) as u32;
+                    indices.extend([first, first + 1, first + 2, first, first + 2, first + 3]);
+                }
+                let normals = vec![[0.0, 0.0, 1.0]; positions.len()];
+                let uvs = vec![[0.0, 0.0]; positions.len()];
+
+                let mut mesh = Mesh::new(PrimitiveTopology::TriangleList);
+                mesh.insert_attribute(Mesh::ATTRIBUTE_POSITION, positions);
+                mesh.insert_attribute(Mesh::ATTRIBUTE_NORMAL, normals);
+                mesh.insert_attribute(Mesh::ATTRIBUTE_UV_0, uvs);
+                mesh.set_indices(Some(Indices::U32(indices)));
+                mesh
+            }
+        }
+    }
+
+    /// Builds a collider in this shape
+    fn collider(&self) -> Collider {
+        match self {
+            ScoreAreaShape::Circle { radius } => Collider::ball(*radius),
+            ScoreAreaShape::Rectangle { width, height } => {
+                Collider::cuboid(width / 2.0, height / 2.0)
+            }
+            ScoreAreaShape::Arc { .. } => Collider::compound(
+                self.arc_segments()
+                    .iter()
+                    .filter_map(|corners| Collider::convex_hull(corners))
+                    .map(|segment| (Vec2::ZERO, 0.0, segment))
+                    .collect(),
+            ),
+        }
+    }
+
+    /// Gets the corners of each of the quads that make up this shape, if it's an arc, going counterclockwise starting from the inner edge
+    fn arc_segments(&self) -> Vec<[Vec2; 4]> {
+        let ScoreAreaShape::Arc {
+            radius,
+            thickness,
+            start_angle,
+            end_angle,
+        } = self
+        else {
+            return Vec::new();
+        };
+
+        let inner_radius = (radius - (thickness / 2.0)).max(0.0);
+        let outer_radius = radius + (thickness / 2.0);
+        let angle_per_segment =
+            (end_angle - start_angle).to_radians() / SCORE_AREA_ARC_SEGMENTS as f32;
+        let point_at = |segment: usize, ring_radius: f32| {
+            let angle = start_angle.to_radians() + (angle_per_segment * segment as f32);
+            Vec2::new(angle.cos(), angle.sin()) * ring_radius
+        };
+
+        (0..SCORE_AREA_ARC_SEGMENTS)
+            .map(|i| {
+                [
+                    point_at(i, inner_radius),
+                    point_at(i, outer_radius),
+                    point_at(i + 1, outer_radius),
+                    point_at(i + 1, inner_radius),
+                ]
+            })
+            .collect()
+    }
+}
+
+#[derive(Clone, Serialize, Deserialize)]
+enum ScoreAreaMovement {
+    /// Circles around the provided point, turning to keep the same side facing it
+    Orbit { center: Vec2, period: Duration },
+    /// Slides back and forth between the score area's starting position and the provided position
+    Slide { to: Vec2, period: Duration },
+    /// Grows and shrinks between the provided scales
+    Pulse {
+        min_scale: f32,
+        max_scale: f32,
+        period: Duration,
+    },
+}
+
+impl ScoreAreaMovement {
+    /// Determines where a score area that started at the provided transform should be after moving for the provided amount of time
+    fn transform_at(&self, start_transform: &Transform, elapsed: Duration) -> Transform {
+        let period = match self {
+            ScoreAreaMovement::Orbit { period, .. }
+            | ScoreAreaMovement::Slide { period, .. }
+            | ScoreAreaMovement::Pulse { period, .. } => period,
+        };
+        let progress = (elapsed.as_secs_f32() / period.as_secs_f32()).fract();
+        // goes smoothly from 0 to 1 and back again over the course of each period
+        let back_and_forth = (1.0 - (progress * std::f32::consts::TAU).cos()) / 2.0;
+
+        let mut transform = *start_transform;
+        match self {
+            ScoreAreaMovement::Orbit { center, .. } => {
+                let rotation = Quat::from_rotation_z(progress * std::f32::consts::TAU);
+                let center = center.extend(start_transform.translation.z);
+                transform.translation = center + rotation * (start_transform.translation - center);
+                transform.rotation = rotation * start_transform.rotation;
+            }
+            ScoreAreaMovement::Slide { to, .. } => {
+                transform.translation = start_transform
+                    .translation
+                    .lerp(to.extend(start_transform.translation.z), back_and_forth);
+            }
+            ScoreAreaMovement::Pulse {
+                min_scale,
+                max_scale,
+                ..
+            } => {
+                transform.scale = Vec3::splat(min_scale.lerp(max_scale, &back_and_forth));
+            }
+        }
+
+        transform
+    }
+}
+
+#[derive(Component)]
+struct MovingScoreArea {
+    movement: ScoreAreaMovement,
+    start_transform: Transform,
+    started_at: Instant,
+}
+
+#[derive(Clone, Serialize, Deserialize)]
+pub struct ObstacleSettings {
+    /// What kind of obstacle this is
+    kind: ObstacleKind,
+    /// Where the center of the obstacle is
+    pub position: Vec2,
+    /// The rotation of the obstacle, in degrees counterclockwise
+    rotation: f32,
+    /// How bouncy the obstacle is
+    restitution: f32,
+    /// Which collision groups the obstacle is in, and which ones it collides with
+    #[serde(with = "collision_groups_serde")]
+    collision_groups: CollisionGroups,
+    /// The sound to play when a ball hits the obstacle
+    sound: ObstacleSound,
+}
+
+impl ObstacleSettings {
+    /// Builds a round bumper that pushes balls away from its center when they hit it
+    pub fn bumper(position: Vec2, radius: f32) -> ObstacleSettings {
+        ObstacleSettings {
+            kind: ObstacleKind::Bumper {
+                radius,
+                impulse: BUMPER_IMPULSE,
+            },
+            position,
+            rotation: 0.0,
+            restitution: 1.0,
+            collision_groups: CollisionGroups::new(OBSTACLE_COLLISION_GROUP, Group::all()),
+            sound: ObstacleSound::Boop,
+        }
+    }
+
+    /// Builds a static wall segment
+    pub fn wall(position: Vec2, width: f32, height: f32, rotation: f32) -> ObstacleSettings {
+        ObstacleSettings {
+            kind: ObstacleKind::Wall { width, height },
+            position,
+            rotation,
+            restitution: 1.0,
+            collision_groups: CollisionGroups::new(OBSTACLE_COLLISION_GROUP, Group::all()),
+            sound: ObstacleSound::Hit,
+        }
+    }
+
+    /// Builds a barrier that balls can pass through going up (before it's rotated), but that they bounce off of going down
+    fn one_way_barrier(position: Vec2, length: f32, rotation: f32) -> ObstacleSettings {
+        ObstacleSettings {
+            kind: ObstacleKind::OneWayBarrier { length },
+            position,
+            rotation,
+            restitution: 0.8,
+            collision_groups: CollisionGroups::new(OBSTACLE_COLLISION_GROUP, BALL_COLLISION_GROUP),
+            sound: ObstacleSound::Hit,
+        }
+    }
+
+    /// Replaces the collision groups of this obstacle with the provided ones
+    fn with_collision_groups(self, collision_groups: CollisionGroups) -> ObstacleSettings {
+        ObstacleSettings {
+            collision_groups,
+            ..self
+        }
+    }
+}
+
+/// Saves collision groups as the bits of their memberships and filters, since they can't be serialized directly
+mod collision_groups_serde {
+    use bevy_rapier2d::prelude::*;
+    use serde::{Deserialize, Deserializer, Serialize, Serializer};
+
+    pub fn serialize<S: Serializer>(
+        collision_groups: &CollisionGroups,
+        serializer: S,
+    ) -> Result<S::Ok, S::Error> {
+        (
+            collision_groups.memberships.bits(),
+            collision_groups.filters.bits(),
+        )
+            .serialize(serializer)
+    }
+
+    pub fn deserialize<'de, D: Deserializer<'de>>(
+        deserializer: D,
+    ) -> Result<CollisionGroups, D::Error> {
+        let (memberships, filters) = <(u32, u32)>::deserialize(deserializer)?;
+        Ok(CollisionGroups::new(
+            Group::from_bits_truncate(memberships),
+            Group::from_bits_truncate(filters),
+        ))
+    }
+}
+
+#[derive(Component, Clone, Serialize, Deserialize)]
+enum ObstacleKind {
+    /// A circle with the provided radius that adds the provided impulse to balls that hit it
+    Bumper { radius: f32, impulse: f32 },
+    /// A rectangle with the provided width and height
+    Wall { width: f32, height: f32 },
+    /// A line with the provided length that balls can only bounce off of from one side
+    OneWayBarrier { length: f32 },
+}
+
+#[derive(Component, Clone, Copy, Serialize, Deserialize)]
+pub enum ObstacleSound {
+    Hit,
+    Boop,
+}
+
+#[derive(Component)]
+struct OneWayBarrier;
+
+#[derive(SystemParam)]
+pub struct GamePhysicsHooks<'w, 's> {
+    one_way_barriers: Query<'w, 's, (), With<OneWayBarrier>>,
+    balls: Query<'w, 's, (), With<Ball>>,
+    duplicate_cooldowns: Query<'w, 's, &'static DuplicateCooldown>,
+    level_settings: Res<'w, LevelSettings>,
+}
+
+impl BevyPhysicsHooks for GamePhysicsHooks<'_, '_> {
+    fn modify_solver_contacts(&self, context: ContactModificationContextView) {
+        const CONTACT_CONFIGURATION_UNKNOWN: u32 = 0;
+        const CONTACT_CURRENTLY_ALLOWED: u32 = 1;
+        const CONTACT_CURRENTLY_FORBIDDEN: u32 = 2;
+
+        if self.balls.contains(context.collider1()) && self.balls.contains(context.collider2()) {
+            if self
+                .duplicate_cooldowns
+                .get(context.collider1())
+                .is_ok_and(|cooldown| cooldown.twin == context.collider2())
+            {
+                // a ball that was just duplicated would be pushed violently away from its copy, since they start out overlapping
+                context.raw.solver_contacts.clear();
+                return;
+            }
+
+            // balls bounce off each other differently than they bounce off everything else
+            if let Some(restitution) = self.level_settings.ball_collision_restitution {
+                for contact in context.raw.solver_contacts.iter_mut() {
+                    contact.restitution = restitution;
+                }
+            }
+            return;
+        }
+
+        // find the contact normal pointing out of the barrier, in the barrier's local space
+        let barrier_normal = if self.one_way_barriers.contains(context.collider1()) {
+            context.raw.manifold.local_n1
+        } else if self.one_way_barriers.contains(context.collider2()) {
+            context.raw.manifold.local_n2
+        } else {
+            return;
+        };
+
+        // balls can only bounce off of the top side
+        let contact_is_ok = barrier_normal.y >= ONE_WAY_BARRIER_ALLOWED_ANGLE.cos();
+
+        match *context.raw.user_data {
+            CONTACT_CONFIGURATION_UNKNOWN => {
+                if contact_is_ok {
+                    *context.raw.user_data = CONTACT_CURRENTLY_ALLOWED;
+                } else {
+                    context.raw.solver_contacts.clear();
+                    // the normal can be zero if the objects are exactly touching, in which case it's not clear which side the ball is on yet
+                    if barrier_normal.norm_squared() > 0.1 {
+                        *context.raw.user_data = CONTACT_CURRENTLY_FORBIDDEN;
+                    }
+                }
+            }
+            CONTACT_CURRENTLY_FORBIDDEN => {
+                // keep letting the ball through until it's all the way through
+                if contact_is_ok && context.raw.solver_contacts.iter().all(|c| c.dist > 0.0) {
+                    *context.raw.user_data = CONTACT_CURRENTLY_ALLOWED;
+                } else {
+                    context.raw.solver_contacts.clear();
+                }
+            }
+            _ => {
+                if context.raw.solver_contacts.is_empty() {
+                    *context.raw.user_data = CONTACT_CONFIGURATION_UNKNOWN;
+                }
+            }
+        }
+    }
+}
+
+#[derive(Clone, Serialize, Deserialize)]
+struct PortalPairSettings {
+    /// Where the centers of the two portals are
+    positions: [Vec2; 2],
+    /// The directions the two portals face, in degrees counterclockwise from the positive X axis. Balls leave a portal moving in the direction it faces.
+    rotations: [f32; 2],
+}
+
+#[derive(Component)]
+struct Portal {
+    /// The portal that balls entering this one will come out of
+    exit: Entity,
+}
+
+#[derive(Clone, Serialize, Deserialize)]
+struct ForceFieldSettings {
+    /// What kind of force field this is
+    kind: ForceFieldKind,
+    /// Where the center of the force field is
+    position: Vec2,
+}
+
+impl ForceFieldSettings {
+    /// Builds a field that pulls balls towards its center, more strongly the closer they are. A negative strength pushes balls away instead.
+    fn gravity_well(position: Vec2, radius: f32, strength: f32) -> ForceFieldSettings {
+        ForceFieldSettings {
+            kind: ForceFieldKind::GravityWell { radius, strength },
+            position,
+        }
+    }
+
+    /// Builds a field that swirls balls around its center, counterclockwise for positive strengths and clockwise for negative ones
+    fn vortex(position: Vec2, radius: f32, strength: f32) -> ForceFieldSettings {
+        ForceFieldSettings {
+            kind: ForceFieldKind::Vortex { radius, strength },
+            position,
+        }
+    }
+
+    /// Builds a rectangular field that pushes balls in one direction
+    fn wind(position: Vec2, size: Vec2, acceleration: Vec2) -> ForceFieldSettings {
+        ForceFieldSettings {
+            kind: ForceFieldKind::Wind { size, acceleration },
+            position,
+        }
+    }
+}
+
+#[derive(Component, Clone, Serialize, Deserialize)]
+enum ForceFieldKind {
+    GravityWell { radius: f32, strength: f32 },
+    Vortex { radius: f32, strength: f32 },
+    Wind { size: Vec2, acceleration: Vec2 },
+}
+
+impl ForceFieldKind {
+    /// Determines the acceleration this field applies to a ball at the provided offset from the center of the field
+    fn acceleration_at(&self, offset: Vec2) -> Vec2 {
+        match self {
+            ForceFieldKind::GravityWell { radius, strength } => {
+                let falloff = (1.0 - offset.length() / radius).max(0.0);
+                -offset.normalize_or_zero() * *strength * falloff
+            }
+            ForceFieldKind::Vortex { radius, strength } => {
+                let falloff = (1.0 - offset.length() / radius).max(0.0);
+                offset.normalize_or_zero().perp() * *strength * falloff
+            }
+            ForceFieldKind::Wind { size, acceleration } => {
+                if offset.abs().cmple(*size / 2.0).all() {
+                    *acceleration
+                } else {
+                    Vec2::ZERO
+                }
+            }
+        }
+    }
+}
+
+#[derive(Clone, Copy, Serialize, Deserialize)]
+struct BallProperties {
+    /// The radius of the ball
+    radius: f32,
+    /// The mass of the ball, in kilograms
+    mass: f32,
+    /// How bouncy the ball is
+    restitution: f32,
+    /// How quickly the ball slows down on its own
+    linear_damping: f32,
+}
+
+impl BallProperties {
+    /// Builds properties for a ball of the provided radius, with the mass it would have by default
+    fn with_radius(radius: f32) -> BallProperties {
+        let radius_meters = radius / PIXELS_PER_METER;
+        BallProperties {
+            radius,
+            mass: std::f32::consts::PI * radius_meters * radius_meters,
+            restitution: BALL_RESTITUTION,
+            linear_damping: BALL_LINEAR_DAMPING,
+        }
+    }
+}
+
+#[derive(Clone, Serialize, Deserialize)]
+struct BallPropertiesSettings {
+    /// Properties for regular balls whose type doesn't have properties defined in `by_type`
+    default: BallProperties,
+    /// Properties for regular balls of specific types
+    by_type: HashMap<BallType, BallProperties>,
+    /// Properties for balls that have had the extra points effect applied
+    extra_points: BallProperties,
+    /// Properties for chameleon balls
+    chameleon: BallProperties,
+}
+
+impl Default for BallPropertiesSettings {
+    fn default() -> Self {
+        BallPropertiesSettings {
+            default: BallProperties::with_radius(BALL_SIZE),
+            by_type: HashMap::new(),
+            extra_points: BallProperties::with_radius(EXTRA_POINT_BALL_SIZE),
+            chameleon: BallProperties::with_radius(BALL_SIZE),
+        }
+    }
+}
+
+impl BallPropertiesSettings {
+    /// Gets the properties for a newly spawned ball
+    fn for_ball(&self, ball_type: BallType, chameleon: bool) -> &BallProperties {
+        if chameleon {
+            &self.chameleon
+        } else {
+            self.by_type.get(&ball_type).unwrap_or(&self.default)
+        }
+    }
+}
+
+#[derive(Resource)]
+pub struct UnlockedSides(pub Vec<SideType>);
+
+#[derive(Resource, Clone, Serialize, Deserialize)]
+pub struct ConfiguredSides(pub HashMap<SideId, SideType>);
+
+/// The sides the second player has configured, when there are two players
+#[derive(Resource)]
+pub struct PlayerTwoConfiguredSides(pub ConfiguredSides);
+
+impl ConfiguredSides {
+    /// Gets the type of the side with the provided ID. Panics if the side is not configured.
+    pub fn get(&self, side_id: &SideId) -> SideType {
+        *self
+            .0
+            .get(side_id)
+            .unwrap_or_else(|| panic!("side {side_id:?} should be configured"))
+    }
+}
+
+/// The layers of the background music that's playing during a level, and how loud each of them currently is
+#[derive(Resource)]
+struct GameMusicController {
+    main: Handle<AudioSink>,
+    tension: Handle<AudioSink>,
+    /// How loud the main track is, from 0.0 to 1.0, before the audio settings are applied
+    main_level: f32,
+    /// How loud the tension layer is, from 0.0 to 1.0, before the audio settings are applied
+    tension_level: f32,
+}
+
+#[derive(Resource)]
+pub struct Score(pub i32);
+
+/// The number of times each type of side has been hit by a ball during the current level
+#[derive(Resource, Default)]
+pub struct SideEffectCounts(pub HashMap<SideType, u32>);
+
+/// The way the game is being played
+#[derive(Resource, Clone, Copy, PartialEq, Eq, Default)]
+pub enum GameMode {
+    /// Levels one after another, unlocking new sides along the way
+    #[default]
+    Campaign,
+    /// A single level that keeps getting harder until the player runs out of lives
+    Endless,
+    /// Levels one after another, trying to reach each level's minimum score as fast as possible
+    TimeAttack,
+    /// A single level generated from the current date, so it's the same for everyone that plays it that day
+    DailyChallenge,
+    /// Two players competing to get balls into their own score areas before time runs out
+    Versus,
+    /// The bot playing a campaign level by itself, shown when the menu is left alone for a while
+    Demo,
+    /// A level from the level editor being tried out
+    EditorTest,
+}
+
+impl GameMode {
+    /// Whether levels in this mode show the time left until they end
+    fn counts_down(&self) -> bool {
+        matches!(
+            self,
+            GameMode::Campaign
+                | GameMode::DailyChallenge
+                | GameMode::Versus
+                | GameMode::Demo
+                | GameMode::EditorTest
+        )
+    }
+}
+
+/// Run condition for systems that should only run when the time left in the level is shown
+fn counting_down(game_mode: Res<GameMode>) -> bool {
+    game_mode.counts_down()
+}
+
+/// Each player's score in versus mode
+#[derive(Resource)]
+pub struct VersusScores(pub HashMap<Player, i32>);
+
+impl VersusScores {
+    /// Gets the provided player's score
+    pub fn get(&self, player: Player) -> i32 {
+        self.0.get(&player).copied().unwrap_or_default()
+    }
+
+    /// Gets the player with the highest score, or `None` if it's a tie
+    pub fn winner(&self) -> Option<Player> {
+        match self.get(Player::One).cmp(&self.get(Player::Two)) {
+            std::cmp::Ordering::Greater => Some(Player::One),
+            std::cmp::Ordering::Less => Some(Player::Two),
+            std::cmp::Ordering::Equal => None,
+        }
+    }
+}
+
+/// The player that gets points from a score area in versus mode
+#[derive(Component)]
+struct ScoreAreaOwner(Player);
+
+/// The daily challenge being played
+#[derive(Resource)]
+pub struct DailyChallenge {
+    /// The number of days since the Unix epoch of the day this challenge is for
+    pub day: u64,
+    /// Whether this attempt won't be recorded, because the challenge has already been attempted today
+    pub practice: bool,
+}
+
+impl DailyChallenge {
+    /// Gets the daily challenge for the current day
+    pub fn today() -> DailyChallenge {
+        DailyChallenge {
+            day: current_day(),
+            practice: false,
+        }
+    }
+
+    /// Gets the date this challenge is for, formatted like 2023-04-01
+    pub fn date(&self) -> String {
+        format_date(self.day)
+    }
+}
+
+/// The scores of the scored attempts at daily challenges, by day
+#[derive(Resource, Default, Serialize, Deserialize)]
+pub struct DailyChallengeScores(pub HashMap<u64, i32>);
+
+/// The fastest times each level has been completed in in time attack mode, by level ID
+#[derive(Resource, Default, Serialize, Deserialize)]
+pub struct TimeAttackBests(pub HashMap<usize, Duration>);
+
+/// How the last time attack level went
+#[derive(Resource)]
+pub struct TimeAttackResult {
+    /// How long it took to reach the target score, or `None` if time ran out first
+    pub time: Option<Duration>,
+    /// The best time for the level before this attempt
+    pub previous_best: Option<Duration>,
+}
+
+/// How many more times balls can go into the wrong score area before an endless run ends
+#[derive(Resource)]
+struct Lives(u32);
+
+/// How long the player lasted in the last endless run
+#[derive(Resource)]
+pub struct TimeSurvived(pub Duration);
+
+#[derive(Resource)]
+struct LevelStartTime(Instant);
+
+/// Random number generator for things that happen during the level, like which balls spawn where
+#[derive(Resource)]
+struct LevelRng(ChaCha8Rng);
+
+/// Random number generator for which types chameleon balls change to. This is kept separate from the level's random number generator so that how many chameleon balls have changed doesn't affect which balls spawn.
+#[derive(Resource)]
+struct ChameleonRng(ChaCha8Rng);
+
+/// The seed the level's random number generator started with
+#[derive(Resource)]
+pub struct LevelSeed(pub u64);
+
+#[derive(Resource)]
+struct LevelEndTime(Instant);
+
+#[derive(Resource)]
+pub struct ColorBlindMode(bool);
+
+#[derive(Component)]
+struct LoadingComponent;
+
+#[derive(Component)]
+struct LoadingText;
+
+#[derive(Component)]
+struct GameComponent;
+
+#[derive(Component)]
+pub struct PlayerShape;
+
+/// Marks a player shape as controlled by the built-in bot
+#[derive(Component)]
+struct Bot;
+
+/// Which player a player shape or side belongs to
+#[derive(Component, PartialEq, Eq, Hash, Clone, Copy, Debug, Serialize, Deserialize)]
+pub enum Player {
+    One,
+    Two,
+}
+
+impl Player {
+    /// Gets the name of this player
+    pub fn name(&self) -> &str {
+        match self {
+            Player::One => "player 1",
+            Player::Two => "player 2",
+        }
+    }
+
+    /// Gets the other player
+    pub fn opponent(&self) -> Player {
+        match self {
+            Player::One => Player::Two,
+            Player::Two => Player::One,
+        }
+    }
+
+    /// Gets the color to fill this player's shape with
+    fn shape_color(&self) -> Color {
+        match self {
+            Player::One => PLAYER_ONE_SHAPE_COLOR,
+            Player::Two => PLAYER_TWO_SHAPE_COLOR,
+        }
+    }
+}
+
+#[derive(Component, Eq, PartialEq, Hash, Clone, Copy, Debug, Serialize, Deserialize)]
+pub struct SideId(pub usize);
+
+impl SideId {
+    /// Finds the ID of the opposite side
+    fn opposite_side(&self) -> SideId {
+        SideId((self.0 + (PLAYER_SHAPE_SIDES / 2)) % PLAYER_SHAPE_SIDES)
+    }
+}
+
+#[derive(Component, PartialEq, Debug, Eq, Hash, Clone, Copy, Serialize, Deserialize)]
+pub enum SideType {
+    NothingSpecial,
     SpeedUp,
     FreezeOthers,
     BounceBackwards,
@@ -525,8 +2280,26 @@ pub enum SideType {
 }
 
 impl SideType {
+    pub const ALL: [SideType; 9] = [
+        SideType::NothingSpecial,
+        SideType::SpeedUp,
+        SideType::FreezeOthers,
+        SideType::BounceBackwards,
+        SideType::Destroy,
+        SideType::Duplicate,
+        SideType::ResizeScoreAreas,
+        SideType::ExtremeBounce,
+        SideType::ExtraPoints,
+    ];
+
     /// Adds the effect component that corresponds with this side to the provided entity
-    fn add_side_effect(&self, entity: Entity, side_id: SideId, commands: &mut Commands) {
+    fn add_side_effect(
+        &self,
+        entity: Entity,
+        player: Player,
+        side_id: SideId,
+        commands: &mut Commands,
+    ) {
         match self {
             SideType::NothingSpecial => (),
             SideType::SpeedUp => {
@@ -536,9 +2309,10 @@ impl SideType {
                 commands.entity(entity).insert(FreezeOthersEffect);
             }
             SideType::BounceBackwards => {
-                commands
-                    .entity(entity)
-                    .insert(BounceBackwardsEffect { side_hit: side_id });
+                commands.entity(entity).insert(BounceBackwardsEffect {
+                    player,
+                    side_hit: side_id,
+                });
             }
             SideType::Destroy => {
                 commands.entity(entity).insert(DestroyEffect);
@@ -604,6 +2378,7 @@ struct FreezeOthersEffect;
 
 #[derive(Component)]
 struct BounceBackwardsEffect {
+    player: Player,
     side_hit: SideId,
 }
 
@@ -616,6 +2391,8 @@ struct DuplicateEffect;
 #[derive(Component)]
 struct DuplicateCooldown {
     remove_at: Instant,
+    /// The other ball from the same duplication. The two balls start out on top of each other, so they don't collide with each other until the cooldown is over.
+    twin: Entity,
 }
 
 #[derive(Component)]
@@ -624,6 +2401,22 @@ struct ResizeScoreAreasEffect;
 #[derive(Component)]
 struct ExtremeBounceEffect;
 
+#[derive(Component)]
+struct PortalEffect {
+    exit: Entity,
+}
+
+#[derive(Component)]
+struct PortalCooldown {
+    remove_at: Instant,
+}
+
+#[derive(Component)]
+struct BumpEffect {
+    bumper_position: Vec2,
+    impulse: f32,
+}
+
 #[derive(Component, Clone, Copy)]
 struct ExtraPointsEffect;
 
@@ -647,68 +2440,74 @@ struct Ball {
     points: u16,
 }
 
-#[derive(PartialEq, Clone, Copy)]
-enum BallType {
-    A,
-    B,
-    C,
-    D,
+#[derive(Component, Clone)]
+struct ChameleonBall {
+    /// When the ball will next change its type
+    next_change_at: Instant,
+}
+
+/// Colors for each ball type, indexed by ball type, as (regular color, color-blind mode color)
+const BALL_TYPE_PALETTE: [(Color, Color); 6] = [
+    (Color::ORANGE_RED, Color::rgb(1.0, 0.36, 0.58)),
+    (Color::LIME_GREEN, Color::rgb(0.6, 0.88, 0.0)),
+    (Color::YELLOW, Color::YELLOW),
+    (Color::rgb(0.0, 0.75, 1.0), Color::rgb(0.0, 0.75, 1.0)),
+    (Color::rgb(0.65, 0.35, 1.0), Color::rgb(0.55, 0.35, 0.95)),
+    (Color::rgb(1.0, 0.4, 0.8), Color::rgb(1.0, 0.55, 0.0)),
+];
+
+#[derive(PartialEq, Eq, Hash, Clone, Copy, Serialize, Deserialize)]
+pub struct BallType(usize);
+
+impl BallType {
+    pub const A: BallType = BallType(0);
+    pub const B: BallType = BallType(1);
+    pub const C: BallType = BallType(2);
+    pub const D: BallType = BallType(3);
+    pub const E: BallType = BallType(4);
+    pub const F: BallType = BallType(5);
 }
 
 impl BallType {
     /// Generates a random ball type
     fn random<R: Rng>(level_settings: &LevelSettings, rng: &mut R) -> BallType {
-        if level_settings.type_b_active && level_settings.type_d_active {
-            match rng.gen_range(0..=3) {
-                0 => BallType::A,
-                1 => BallType::B,
-                2 => BallType::C,
-                3 => BallType::D,
-                _ => unreachable!(),
-            }
-        } else if level_settings.type_b_active {
-            match rng.gen_range(0..=2) {
-                0 => BallType::A,
-                1 => BallType::B,
-                2 => BallType::C,
-                _ => unreachable!(),
-            }
-        } else if level_settings.type_d_active {
-            match rng.gen_range(0..=2) {
-                0 => BallType::A,
-                1 => BallType::C,
-                2 => BallType::D,
-                _ => unreachable!(),
-            }
-        } else {
-            match rng.gen_range(0..=1) {
-                0 => BallType::A,
-                1 => BallType::C,
-                _ => unreachable!(),
-            }
-        }
+        *level_settings
+            .active_ball_types()
+            .choose(rng)
+            .expect("at least one ball type should be active")
+    }
+
+    /// Generates a random ball type that is different from this one, if there are any others active
+    fn random_other<R: Rng>(&self, level_settings: &LevelSettings, rng: &mut R) -> BallType {
+        let other_types = level_settings
+            .active_ball_types()
+            .into_iter()
+            .filter(|ball_type| ball_type != self)
+            .collect::<Vec<BallType>>();
+
+        other_types.choose(rng).copied().unwrap_or(*self)
     }
 }
 
 impl BallType {
-    /// Gets the color that corresponds to this ball type
-    fn color(&self, color_blind_mode: &ColorBlindMode) -> Color {
+    /// Gets the color that corresponds to this ball type. Panics if there's no palette entry for this ball type.
+    pub fn color(&self, color_blind_mode: &ColorBlindMode) -> Color {
+        let (regular_color, color_blind_color) = BALL_TYPE_PALETTE
+            .get(self.0)
+            .unwrap_or_else(|| panic!("ball type {} should have a palette entry", self.0));
+
         if color_blind_mode.0 {
-            match self {
-                BallType::A => Color::rgb(1.0, 0.36, 0.58),
-                BallType::B => Color::rgb(0.6, 0.88, 0.0),
-                BallType::C => Color::YELLOW,
-                BallType::D => Color::rgb(0.0, 0.75, 1.0),
-            }
+            *color_blind_color
         } else {
-            match self {
-                BallType::A => Color::ORANGE_RED,
-                BallType::B => Color::LIME_GREEN,
-                BallType::C => Color::YELLOW,
-                BallType::D => Color::rgb(0.0, 0.75, 1.0),
-            }
+            *regular_color
         }
     }
+
+    /// Gets the ball type the provided number of steps after this one, wrapping around after the last one
+    pub fn cycled(&self, steps: i32) -> BallType {
+        let ball_types = BALL_TYPE_PALETTE.len() as i32;
+        BallType((self.0 as i32 + steps).rem_euclid(ball_types) as usize)
+    }
 }
 
 #[derive(Component)]
@@ -723,15 +2522,44 @@ struct AnimateScoreAreaHit {
 #[derive(Component)]
 struct LevelText;
 
+#[derive(Component)]
+struct LivesText;
+
 #[derive(Component)]
 struct ScoreText;
 
+#[derive(Component)]
+struct VersusScoreText(Player);
+
 #[derive(Component)]
 struct TimeText;
 
+#[derive(Component)]
+struct SplitText;
+
 #[derive(Component)]
 struct RotateSensitivityText;
 
+/// Starts the test level for the key that was pressed. Only used in dev mode.
+pub fn start_test_level(
+    keycode: Res<Input<KeyCode>>,
+    mut game_mode: ResMut<GameMode>,
+    mut level_settings: ResMut<LevelSettings>,
+    mut next_state: ResMut<NextState<GameState>>,
+) {
+    let Some(test_level) = TEST_LEVEL_KEYS
+        .iter()
+        .position(|key| keycode.just_pressed(*key))
+        .and_then(|index| LevelSettings::test_levels().into_iter().nth(index))
+    else {
+        return;
+    };
+
+    *game_mode = GameMode::Campaign;
+    *level_settings = test_level;
+    next_state.set(GameState::GameLoading);
+}
+
 /// Sets up the loading screen.
 fn loading_setup(mut commands: Commands, asset_server: Res<AssetServer>) {
     commands
@@ -782,165 +2610,134 @@ fn game_setup(
     level_settings: Res<LevelSettings>,
     configured_sides: Res<ConfiguredSides>,
     color_blind_mode: Res<ColorBlindMode>,
+    mut rapier_configuration: ResMut<RapierConfiguration>,
+    game_mode: Res<GameMode>,
+    daily_challenge: Option<Res<DailyChallenge>>,
+    (two_players, bot_players): (Res<TwoPlayers>, Res<BotPlayers>),
+    player_two_configured_sides: Res<PlayerTwoConfiguredSides>,
+    gamepads: Res<Gamepads>,
+    time: Res<Time>,
 ) {
-    spawn_player_shape(
-        &mut commands,
-        &mut meshes,
-        &mut materials,
-        &image_assets,
-        &configured_sides,
-        Transform::from_translation(Vec3::new(0., 0., 0.)),
-    )
-    .insert(GameComponent);
+    rapier_configuration.gravity = level_settings.gravity;
 
-    // score areas
-    commands
-        .spawn(MaterialMesh2dBundle {
-            mesh: meshes
-                .add(shape::Circle::new(SCORE_AREA_SIZE).into())
-                .into(),
-            material: materials.add(ColorMaterial::from(color_for_score_area(
-                &ScoreArea(BallType::A),
-                &color_blind_mode,
-            ))),
-            ..default()
-        })
-        .insert(Collider::ball(SCORE_AREA_SIZE))
-        .insert(Sensor)
-        .insert(Transform::from_translation(Vec3::new(
-            -PLAY_AREA_RADIUS,
-            PLAY_AREA_RADIUS,
-            0.0,
-        )))
-        .insert(GameComponent)
-        .insert(ScoreArea(BallType::A));
+    let players = if two_players.0 {
+        vec![
+            (
+                Player::One,
+                &*configured_sides,
+                Vec3::new(-TWO_PLAYER_SPAWN_OFFSET, 0., 0.),
+            ),
+            (
+                Player::Two,
+                &player_two_configured_sides.0,
+                Vec3::new(TWO_PLAYER_SPAWN_OFFSET, 0., 0.),
+            ),
+        ]
+    } else {
+        vec![(Player::One, &*configured_sides, Vec3::new(0., 0., 0.))]
+    };
 
-    if level_settings.type_b_active {
-        commands
-            .spawn(MaterialMesh2dBundle {
-                mesh: meshes
-                    .add(shape::Circle::new(SCORE_AREA_SIZE).into())
-                    .into(),
-                material: materials.add(ColorMaterial::from(color_for_score_area(
-                    &ScoreArea(BallType::B),
-                    &color_blind_mode,
-                ))),
-                ..default()
-            })
-            .insert(Collider::ball(SCORE_AREA_SIZE))
-            .insert(Sensor)
-            .insert(Transform::from_translation(Vec3::new(
-                PLAY_AREA_RADIUS,
-                PLAY_AREA_RADIUS,
-                0.0,
-            )))
-            .insert(GameComponent)
-            .insert(ScoreArea(BallType::B));
-    }
+    for (player, player_configured_sides, position) in players {
+        let mut player_shape = spawn_player_shape(
+            &mut commands,
+            &mut meshes,
+            &mut materials,
+            &image_assets,
+            player,
+            level_settings.player_sides(player_configured_sides),
+            Transform::from_translation(position),
+        );
+        player_shape.insert(GameComponent);
 
-    commands
-        .spawn(MaterialMesh2dBundle {
-            mesh: meshes
-                .add(shape::Circle::new(SCORE_AREA_SIZE).into())
-                .into(),
-            material: materials.add(ColorMaterial::from(color_for_score_area(
-                &ScoreArea(BallType::C),
-                &color_blind_mode,
-            ))),
-            ..default()
-        })
-        .insert(Collider::ball(SCORE_AREA_SIZE))
-        .insert(Sensor)
-        .insert(Transform::from_translation(Vec3::new(
-            PLAY_AREA_RADIUS,
-            -PLAY_AREA_RADIUS,
-            0.0,
-        )))
-        .insert(GameComponent)
-        .insert(ScoreArea(BallType::C));
+        if bot_players.0.contains(&player) {
+            player_shape.insert(Bot);
+        }
+    }
 
-    if level_settings.type_d_active {
-        commands
-            .spawn(MaterialMesh2dBundle {
-                mesh: meshes
-                    .add(shape::Circle::new(SCORE_AREA_SIZE).into())
-                    .into(),
-                material: materials.add(ColorMaterial::from(color_for_score_area(
-                    &ScoreArea(BallType::D),
-                    &color_blind_mode,
-                ))),
-                ..default()
-            })
-            .insert(Collider::ball(SCORE_AREA_SIZE))
-            .insert(Sensor)
-            .insert(Transform::from_translation(Vec3::new(
-                -PLAY_AREA_RADIUS,
-                -PLAY_AREA_RADIUS,
-                0.0,
-            )))
-            .insert(GameComponent)
-            .insert(ScoreArea(BallType::D));
+    // score areas
+    for score_area_settings in &level_settings.score_areas {
+        let mut score_area = spawn_score_area(
+            &mut commands,
+            &mut meshes,
+            &mut materials,
+            score_area_settings,
+            &color_blind_mode,
+            now(&time),
+        );
+        score_area.insert(GameComponent);
+
+        if let Some(owner) = score_area_settings.owner {
+            score_area.insert(ScoreAreaOwner(owner));
+
+            // owner label, placed a bit towards the middle of the arena so it's on screen
+            let label_position = score_area_settings.position
+                - score_area_settings.position.normalize_or_zero() * SCORE_AREA_SIZE * 0.5;
+            commands
+                .spawn(Text2dBundle {
+                    text: Text::from_section(
+                        owner.name(),
+                        TextStyle {
+                            font: asset_server.load(MONO_FONT),
+                            font_size: VERSUS_OWNER_LABEL_FONT_SIZE,
+                            color: Color::rgba(1.0, 1.0, 1.0, 0.5),
+                        },
+                    )
+                    .with_alignment(TextAlignment::Center),
+                    transform: Transform::from_translation(label_position.extend(1.0)),
+                    ..default()
+                })
+                .insert(GameComponent);
+        }
     }
 
-    // left wall
-    commands
-        .spawn(SpriteBundle {
-            transform: Transform::from_translation(Vec3::new(-PLAY_AREA_RADIUS * 2.0, 0.0, 0.0)),
-            sprite: Sprite {
-                color: WALL_COLOR,
-                custom_size: Some(Vec2::new(PLAY_AREA_RADIUS * 2.0, PLAY_AREA_RADIUS * 2.0)),
-                ..default()
-            },
-            ..default()
-        })
-        .insert(Collider::cuboid(PLAY_AREA_RADIUS, PLAY_AREA_RADIUS))
-        .insert(Restitution::coefficient(1.0))
+    // obstacles
+    for obstacle_settings in &level_settings.obstacles {
+        spawn_obstacle(
+            &mut commands,
+            &mut meshes,
+            &mut materials,
+            obstacle_settings,
+        )
         .insert(GameComponent);
+    }
 
-    // top wall
-    commands
-        .spawn(SpriteBundle {
-            transform: Transform::from_translation(Vec3::new(0.0, PLAY_AREA_RADIUS * 2.0, 0.0)),
-            sprite: Sprite {
-                color: WALL_COLOR,
-                custom_size: Some(Vec2::new(PLAY_AREA_RADIUS * 2.0, PLAY_AREA_RADIUS * 2.0)),
-                ..default()
-            },
-            ..default()
-        })
-        .insert(Collider::cuboid(PLAY_AREA_RADIUS, PLAY_AREA_RADIUS))
-        .insert(Restitution::coefficient(1.0))
-        .insert(GameComponent);
+    // portals
+    for portal_pair_settings in &level_settings.portals {
+        let portals = [0, 1].map(|i| {
+            spawn_portal(
+                &mut commands,
+                &mut meshes,
+                &mut materials,
+                portal_pair_settings.positions[i],
+                portal_pair_settings.rotations[i],
+            )
+            .insert(GameComponent)
+            .id()
+        });
 
-    // right wall
-    commands
-        .spawn(SpriteBundle {
-            transform: Transform::from_translation(Vec3::new(PLAY_AREA_RADIUS * 2.0, 0.0, 0.0)),
-            sprite: Sprite {
-                color: WALL_COLOR,
-                custom_size: Some(Vec2::new(PLAY_AREA_RADIUS * 2.0, PLAY_AREA_RADIUS * 2.0)),
-                ..default()
-            },
-            ..default()
-        })
-        .insert(Collider::cuboid(PLAY_AREA_RADIUS, PLAY_AREA_RADIUS))
-        .insert(Restitution::coefficient(1.0))
-        .insert(GameComponent);
+        commands
+            .entity(portals[0])
+            .insert(Portal { exit: portals[1] });
+        commands
+            .entity(portals[1])
+            .insert(Portal { exit: portals[0] });
+    }
 
-    // bottom wall
-    commands
-        .spawn(SpriteBundle {
-            transform: Transform::from_translation(Vec3::new(0.0, -PLAY_AREA_RADIUS * 2.0, 0.0)),
-            sprite: Sprite {
-                color: WALL_COLOR,
-                custom_size: Some(Vec2::new(PLAY_AREA_RADIUS * 2.0, PLAY_AREA_RADIUS * 2.0)),
-                ..default()
-            },
-            ..default()
-        })
-        .insert(Collider::cuboid(PLAY_AREA_RADIUS, PLAY_AREA_RADIUS))
-        .insert(Restitution::coefficient(1.0))
+    // force fields
+    for force_field_settings in &level_settings.force_fields {
+        spawn_force_field(
+            &mut commands,
+            &mut meshes,
+            &mut materials,
+            force_field_settings,
+        )
         .insert(GameComponent);
+    }
+
+    // walls
+    for wall in spawn_walls(&mut commands, &level_settings.arena) {
+        commands.entity(wall).insert(GameComponent);
+    }
 
     // left sidebar
     commands
@@ -971,7 +2768,22 @@ fn game_setup(
             parent
                 .spawn(
                     TextBundle::from_section(
-                        format!("level {}", level_settings.id),
+                        match *game_mode {
+                            GameMode::Campaign | GameMode::TimeAttack => {
+                                format!("level {}", level_settings.id)
+                            }
+                            GameMode::Endless => "endless".to_string(),
+                            GameMode::Versus => "versus".to_string(),
+                            GameMode::Demo => format!("demo: level {}", level_settings.id),
+                            GameMode::EditorTest => "test play".to_string(),
+                            GameMode::DailyChallenge => {
+                                if daily_challenge.is_some_and(|challenge| challenge.practice) {
+                                    "daily practice".to_string()
+                                } else {
+                                    "daily challenge".to_string()
+                                }
+                            }
+                        },
                         TextStyle {
                             font: asset_server.load(MONO_FONT),
                             font_size: 28.0,
@@ -994,40 +2806,51 @@ fn game_setup(
                 )
                 .insert(LevelText);
 
-            // minimum score display
-            parent.spawn(
-                TextBundle::from_section(
-                    format!("score needed: {}", level_settings.min_score),
-                    TextStyle {
-                        font: asset_server.load(MONO_FONT),
-                        font_size: 22.0,
-                        color: Color::Rgba {
-                            red: 0.75,
-                            green: 0.75,
-                            blue: 0.75,
-                            alpha: 1.0,
-                        },
-                    },
-                )
-                .with_text_alignment(TextAlignment::Center)
-                .with_style(Style {
-                    margin: UiRect {
-                        bottom: Val::Px(5.0),
-                        ..default()
-                    },
-                    ..default()
-                }),
-            );
-
-            // score display
-            parent
-                .spawn(
+            if *game_mode == GameMode::Endless {
+                // lives display
+                parent
+                    .spawn(
+                        TextBundle::from_section(
+                            format!("lives: {ENDLESS_STARTING_LIVES}"),
+                            TextStyle {
+                                font: asset_server.load(MONO_FONT),
+                                font_size: 22.0,
+                                color: Color::Rgba {
+                                    red: 0.75,
+                                    green: 0.75,
+                                    blue: 0.75,
+                                    alpha: 1.0,
+                                },
+                            },
+                        )
+                        .with_text_alignment(TextAlignment::Center)
+                        .with_style(Style {
+                            margin: UiRect {
+                                bottom: Val::Px(5.0),
+                                ..default()
+                            },
+                            ..default()
+                        }),
+                    )
+                    .insert(LivesText);
+            } else if !matches!(*game_mode, GameMode::DailyChallenge | GameMode::Versus) {
+                // minimum score display
+                parent.spawn(
                     TextBundle::from_section(
-                        "score: 0",
+                        if *game_mode == GameMode::TimeAttack {
+                            format!("target score: {}", level_settings.min_score)
+                        } else {
+                            format!("score needed: {}", level_settings.min_score)
+                        },
                         TextStyle {
                             font: asset_server.load(MONO_FONT),
-                            font_size: 33.0,
-                            color: Color::WHITE,
+                            font_size: 22.0,
+                            color: Color::Rgba {
+                                red: 0.75,
+                                green: 0.75,
+                                blue: 0.75,
+                                alpha: 1.0,
+                            },
                         },
                     )
                     .with_text_alignment(TextAlignment::Center)
@@ -1038,10 +2861,89 @@ fn game_setup(
                         },
                         ..default()
                     }),
-                )
-                .insert(ScoreText);
+                );
+            }
+
+            if *game_mode == GameMode::Versus {
+                // score displays for each player
+                for player in [Player::One, Player::Two] {
+                    parent
+                        .spawn(
+                            TextBundle::from_section(
+                                format!("{}: 0", player.name()),
+                                TextStyle {
+                                    font: asset_server.load(MONO_FONT),
+                                    font_size: 33.0,
+                                    color: Color::WHITE,
+                                },
+                            )
+                            .with_text_alignment(TextAlignment::Center)
+                            .with_style(Style {
+                                margin: UiRect {
+                                    bottom: Val::Px(5.0),
+                                    ..default()
+                                },
+                                ..default()
+                            }),
+                        )
+                        .insert(VersusScoreText(player));
+                }
+            } else {
+                // score display
+                parent
+                    .spawn(
+                        TextBundle::from_section(
+                            "score: 0",
+                            TextStyle {
+                                font: asset_server.load(MONO_FONT),
+                                font_size: 33.0,
+                                color: Color::WHITE,
+                            },
+                        )
+                        .with_text_alignment(TextAlignment::Center)
+                        .with_style(Style {
+                            margin: UiRect {
+                                bottom: Val::Px(5.0),
+                                ..default()
+                            },
+                            ..default()
+                        }),
+                    )
+                    .insert(ScoreText);
+            }
         });
 
+    if *game_mode == GameMode::TimeAttack {
+        // split display
+        commands
+            .spawn(
+                TextBundle::from_section(
+                    "no best time yet",
+                    TextStyle {
+                        font: asset_server.load(MONO_FONT),
+                        font_size: SPLIT_FONT_SIZE,
+                        color: Color::rgb(0.75, 0.75, 0.75),
+                    },
+                )
+                .with_text_alignment(TextAlignment::Center)
+                .with_style(Style {
+                    position_type: PositionType::Absolute,
+                    position: UiRect {
+                        top: Val::Px(20.0 + TIMER_FONT_SIZE),
+                        ..default()
+                    },
+                    margin: UiRect {
+                        left: Val::Auto,
+                        right: Val::Auto,
+                        ..default()
+                    },
+                    ..default()
+                }),
+            )
+            .insert(GameComponent)
+            .insert(SplitText);
+    }
+
     // timer display
     commands
         .spawn(
@@ -1096,8 +2998,451 @@ fn game_setup(
         .insert(GameComponent)
         .insert(RotateSensitivityText);
 
+    if matches!(*game_mode, GameMode::Demo | GameMode::EditorTest) {
+        // prompt for how to leave
+        let prompt = if *game_mode == GameMode::Demo {
+            "demo - press any key to return to the menu"
+        } else {
+            "test play - press escape to return to the editor"
+        };
+        commands
+            .spawn(
+                TextBundle::from_section(
+                    prompt,
+                    TextStyle {
+                        font: asset_server.load(MONO_FONT),
+                        font_size: 14.0,
+                        color: Color::rgb(0.9, 0.9, 0.9),
+                    },
+                )
+                .with_style(Style {
+                    position_type: PositionType::Absolute,
+                    position: UiRect {
+                        left: Val::Px(5.0),
+                        bottom: Val::Px(5.0),
+                        ..default()
+                    },
+                    ..default()
+                }),
+            )
+            .insert(GameComponent);
+    } else if two_players.0 && bot_players.0.is_empty() {
+        // controls display
+        let controls_text = if gamepads.iter().next().is_some() {
+            "player 1: wasd + arrows, player 2: gamepad"
+        } else {
+            "player 1: wasd + q/e, player 2: arrows + k/l"
+        };
+        commands
+            .spawn(
+                TextBundle::from_section(
+                    controls_text,
+                    TextStyle {
+                        font: asset_server.load(MONO_FONT),
+                        font_size: 14.0,
+                        color: Color::rgb(0.9, 0.9, 0.9),
+                    },
+                )
+                .with_style(Style {
+                    position_type: PositionType::Absolute,
+                    position: UiRect {
+                        left: Val::Px(5.0),
+                        bottom: Val::Px(5.0),
+                        ..default()
+                    },
+                    ..default()
+                }),
+            )
+            .insert(GameComponent);
+    }
+
+    commands.insert_resource(ViewSize(level_settings.arena.view_size()));
     commands.insert_resource(Score(0));
-    commands.insert_resource(LevelEndTime(Instant::now() + level_settings.duration));
+    commands.insert_resource(SideEffectCounts::default());
+    commands.insert_resource(VersusScores([(Player::One, 0), (Player::Two, 0)].into()));
+    commands.insert_resource(Lives(ENDLESS_STARTING_LIVES));
+    commands.insert_resource(LevelStartTime(now(&time)));
+    let seed = level_settings.seed.unwrap_or_else(rand::random);
+    commands.insert_resource(LevelRng(ChaCha8Rng::seed_from_u64(seed)));
+    let mut chameleon_rng = ChaCha8Rng::seed_from_u64(seed);
+    chameleon_rng.set_stream(CHAMELEON_RNG_STREAM);
+    commands.insert_resource(ChameleonRng(chameleon_rng));
+    commands.insert_resource(LevelSeed(seed));
+    commands.insert_resource(SpawnTimer {
+        next_spawn_time: now(&time),
+        balls_spawned_in_group: 0,
+    });
+    let time_limit = if *game_mode == GameMode::TimeAttack {
+        level_settings.duration * TIME_ATTACK_TIME_LIMIT_MULTIPLIER
+    } else {
+        level_settings.duration
+    };
+    commands.insert_resource(LevelEndTime(now(&time) + time_limit));
+}
+
+/// Spawns walls along each edge of the provided arena, returning the spawned entities
+fn spawn_walls(commands: &mut Commands, arena: &ArenaShape) -> Vec<Entity> {
+    let corners = arena.corners();
+    corners
+        .iter()
+        .enumerate()
+        .map(|(i, corner)| {
+            let edge = corners[(i + 1) % corners.len()] - *corner;
+            // the corners go counterclockwise, so the outside of the arena is to the right of each edge
+            let outward = Vec2::new(edge.y, -edge.x).normalize();
+            let center = *corner + (edge / 2.0) + (outward * WALL_THICKNESS / 2.0);
+            // extend the wall past the corners so there aren't any gaps between walls
+            let size = Vec2::new(edge.length() + (WALL_THICKNESS * 2.0), WALL_THICKNESS);
+
+            commands
+                .spawn(SpriteBundle {
+                    transform: Transform::from_translation(center.extend(0.0))
+                        .with_rotation(Quat::from_rotation_z(edge.y.atan2(edge.x))),
+                    sprite: Sprite {
+                        color: WALL_COLOR,
+                        custom_size: Some(size),
+                        ..default()
+                    },
+                    ..default()
+                })
+                .insert(Collider::cuboid(size.x / 2.0, size.y / 2.0))
+                .insert(Restitution::coefficient(1.0))
+                .id()
+        })
+        .collect()
+}
+
+/// Spawns a still view of the provided level for the level editor, with markers showing where balls spawn. Returns the spawned entities.
+pub fn spawn_level_preview(
+    commands: &mut Commands,
+    meshes: &mut Assets<Mesh>,
+    materials: &mut Assets<ColorMaterial>,
+    level_settings: &LevelSettings,
+    color_blind_mode: &ColorBlindMode,
+) -> Vec<Entity> {
+    let mut entities = spawn_walls(commands, &level_settings.arena);
+
+    for score_area_settings in &level_settings.score_areas {
+        // leave out any movement so score areas are shown where they start
+        let score_area_settings = ScoreAreaSettings {
+            movement: None,
+            ..score_area_settings.clone()
+        };
+        entities.push(
+            spawn_score_area(
+                commands,
+                meshes,
+                materials,
+                &score_area_settings,
+                color_blind_mode,
+                Instant::now(),
+            )
+            .id(),
+        );
+    }
+
+    for obstacle_settings in &level_settings.obstacles {
+        entities.push(spawn_obstacle(commands, meshes, materials, obstacle_settings).id());
+    }
+
+    for portal_pair_settings in &level_settings.portals {
+        for i in 0..2 {
+            entities.push(
+                spawn_portal(
+                    commands,
+                    meshes,
+                    materials,
+                    portal_pair_settings.positions[i],
+                    portal_pair_settings.rotations[i],
+                )
+                .id(),
+            );
+        }
+    }
+
+    for force_field_settings in &level_settings.force_fields {
+        entities.push(spawn_force_field(commands, meshes, materials, force_field_settings).id());
+    }
+
+    for spawn_point in &level_settings.spawn_points {
+        let size = Vec2::new(
+            spawn_point.start_position_range_x.end() - spawn_point.start_position_range_x.start(),
+            spawn_point.start_position_range_y.end() - spawn_point.start_position_range_y.start(),
+        )
+        .max(Vec2::splat(SPAWN_POINT_PREVIEW_MIN_SIZE));
+        let impulse = spawn_point.average_impulse();
+
+        let spawn_point_entity = commands
+            .spawn(SpriteBundle {
+                transform: Transform::from_translation(spawn_point.center().extend(0.1)),
+                sprite: Sprite {
+                    color: SPAWN_POINT_PREVIEW_COLOR,
+                    custom_size: Some(size),
+                    ..default()
+                },
+                ..default()
+            })
+            .with_children(|parent| {
+                // marker pointing the way balls will be launched
+                let direction = impulse.normalize_or_zero();
+                parent.spawn(SpriteBundle {
+                    transform: Transform::from_translation(
+                        (direction * SPAWN_POINT_PREVIEW_MARKER_SIZE.x / 2.0).extend(0.001),
+                    )
+                    .with_rotation(Quat::from_rotation_z(direction.y.atan2(direction.x))),
+                    sprite: Sprite {
+                        color: SPAWN_POINT_PREVIEW_MARKER_COLOR,
+                        custom_size: Some(SPAWN_POINT_PREVIEW_MARKER_SIZE),
+                        ..default()
+                    },
+                    ..default()
+                });
+            })
+            .id();
+        entities.push(spawn_point_entity);
+    }
+
+    entities
+}
+
+/// Spawns a score area based on the provided settings
+fn spawn_score_area<'w, 's, 'a>(
+    commands: &'a mut Commands<'w, 's>,
+    meshes: &mut Assets<Mesh>,
+    materials: &mut Assets<ColorMaterial>,
+    score_area_settings: &ScoreAreaSettings,
+    color_blind_mode: &ColorBlindMode,
+    now: Instant,
+) -> EntityCommands<'w, 's, 'a> {
+    let score_area = ScoreArea(score_area_settings.ball_type);
+    let mut score_area_entity = commands.spawn(MaterialMesh2dBundle {
+        mesh: meshes.add(score_area_settings.shape.mesh()).into(),
+        material: materials.add(ColorMaterial::from(color_for_score_area(
+            &score_area,
+            color_blind_mode,
+        ))),
+        transform: Transform::from_translation(score_area_settings.position.extend(0.0))
+            .with_rotation(Quat::from_rotation_z(
+                score_area_settings.rotation.to_radians(),
+            )),
+        ..default()
+    });
+
+    score_area_entity
+        .insert(score_area_settings.shape.collider())
+        .insert(Sensor)
+        .insert(score_area_settings.shape.clone())
+        .insert(score_area);
+
+    if let Some(movement) = &score_area_settings.movement {
+        score_area_entity.insert(MovingScoreArea {
+            movement: movement.clone(),
+            start_transform: Transform::from_translation(score_area_settings.position.extend(0.0))
+                .with_rotation(Quat::from_rotation_z(
+                    score_area_settings.rotation.to_radians(),
+                )),
+            started_at: now,
+        });
+    }
+
+    score_area_entity
+}
+
+/// Spawns an obstacle based on the provided settings
+fn spawn_obstacle<'w, 's, 'a>(
+    commands: &'a mut Commands<'w, 's>,
+    meshes: &mut Assets<Mesh>,
+    materials: &mut Assets<ColorMaterial>,
+    obstacle_settings: &ObstacleSettings,
+) -> EntityCommands<'w, 's, 'a> {
+    let transform = Transform::from_translation(obstacle_settings.position.extend(0.0))
+        .with_rotation(Quat::from_rotation_z(
+            obstacle_settings.rotation.to_radians(),
+        ));
+
+    let mut obstacle = match obstacle_settings.kind {
+        ObstacleKind::Bumper { radius, .. } => {
+            let mut obstacle = commands.spawn(MaterialMesh2dBundle {
+                mesh: meshes.add(shape::Circle::new(radius).into()).into(),
+                material: materials.add(ColorMaterial::from(BUMPER_COLOR)),
+                transform,
+                ..default()
+            });
+            obstacle.insert(Collider::ball(radius));
+            obstacle
+        }
+        ObstacleKind::Wall { width, height } => {
+            let mut obstacle = commands.spawn(SpriteBundle {
+                transform,
+                sprite: Sprite {
+                    color: WALL_COLOR,
+                    custom_size: Some(Vec2::new(width, height)),
+                    ..default()
+                },
+                ..default()
+            });
+            obstacle.insert(Collider::cuboid(width / 2.0, height / 2.0));
+            obstacle
+        }
+        ObstacleKind::OneWayBarrier { length } => {
+            let mut obstacle = commands.spawn(SpriteBundle {
+                transform,
+                sprite: Sprite {
+                    color: ONE_WAY_BARRIER_COLOR,
+                    custom_size: Some(Vec2::new(length, ONE_WAY_BARRIER_THICKNESS)),
+                    ..default()
+                },
+                ..default()
+            });
+            obstacle
+                .insert(Collider::cuboid(
+                    length / 2.0,
+                    ONE_WAY_BARRIER_THICKNESS / 2.0,
+                ))
+                .insert(ActiveHooks::MODIFY_SOLVER_CONTACTS)
+                .insert(OneWayBarrier);
+            obstacle
+        }
+    };
+
+    obstacle
+        .insert(Restitution {
+            coefficient: obstacle_settings.restitution,
+            combine_rule: CoefficientCombineRule::Multiply,
+        })
+        .insert(obstacle_settings.collision_groups)
+        .insert(obstacle_settings.sound)
+        .insert(obstacle_settings.kind.clone());
+
+    obstacle
+}
+
+/// Spawns one end of a pair of portals
+fn spawn_portal<'w, 's, 'a>(
+    commands: &'a mut Commands<'w, 's>,
+    meshes: &mut Assets<Mesh>,
+    materials: &mut Assets<ColorMaterial>,
+    position: Vec2,
+    rotation: f32,
+) -> EntityCommands<'w, 's, 'a> {
+    let mut portal = commands.spawn(MaterialMesh2dBundle {
+        mesh: meshes.add(shape::Circle::new(PORTAL_RADIUS).into()).into(),
+        material: materials.add(ColorMaterial::from(PORTAL_COLOR)),
+        transform: Transform::from_translation(position.extend(0.0))
+            .with_rotation(Quat::from_rotation_z(rotation.to_radians())),
+        ..default()
+    });
+
+    portal
+        .insert(Collider::ball(PORTAL_RADIUS))
+        .insert(Sensor)
+        .with_children(|parent| {
+            // marker showing which way balls will come out
+            parent.spawn(SpriteBundle {
+                transform: Transform::from_xyz(PORTAL_RADIUS, 0.0, 0.0),
+                sprite: Sprite {
+                    color: PORTAL_COLOR,
+                    custom_size: Some(PORTAL_DIRECTION_MARKER_SIZE),
+                    ..default()
+                },
+                ..default()
+            });
+        });
+
+    portal
+}
+
+/// Spawns a force field
+fn spawn_force_field<'w, 's, 'a>(
+    commands: &'a mut Commands<'w, 's>,
+    meshes: &mut Assets<Mesh>,
+    materials: &mut Assets<ColorMaterial>,
+    force_field_settings: &ForceFieldSettings,
+) -> EntityCommands<'w, 's, 'a> {
+    // slightly behind everything else so the field doesn't hide balls
+    let transform = Transform::from_translation(force_field_settings.position.extend(-0.1));
+    let material = materials.add(ColorMaterial::from(FORCE_FIELD_COLOR));
+
+    let mut force_field = match force_field_settings.kind {
+        ForceFieldKind::GravityWell { radius, .. } => {
+            let mut force_field = commands.spawn(SpatialBundle::from_transform(transform));
+            force_field.with_children(|parent| {
+                // overlapping rings, so the field gets more visible towards the center
+                for i in 0..GRAVITY_WELL_RINGS {
+                    let ring_radius =
+                        radius * (GRAVITY_WELL_RINGS - i) as f32 / GRAVITY_WELL_RINGS as f32;
+                    parent.spawn(MaterialMesh2dBundle {
+                        mesh: meshes.add(shape::Circle::new(ring_radius).into()).into(),
+                        material: material.clone(),
+                        transform: Transform::from_xyz(0.0, 0.0, i as f32 * 0.001),
+                        ..default()
+                    });
+                }
+            });
+            force_field
+        }
+        ForceFieldKind::Vortex { radius, .. } => {
+            let mut force_field = commands.spawn(MaterialMesh2dBundle {
+                mesh: meshes.add(shape::Circle::new(radius).into()).into(),
+                material,
+                transform,
+                ..default()
+            });
+            force_field.with_children(|parent| {
+                // markers around the edge pointing the way balls get swirled
+                for i in 0..VORTEX_SPOKES {
+                    let angle = std::f32::consts::TAU * i as f32 / VORTEX_SPOKES as f32;
+                    parent.spawn(SpriteBundle {
+                        transform: Transform::from_translation(
+                            (Vec2::from_angle(angle) * radius * 0.7).extend(0.001),
+                        )
+                        .with_rotation(Quat::from_rotation_z(angle + std::f32::consts::FRAC_PI_2)),
+                        sprite: Sprite {
+                            color: FORCE_FIELD_MARKER_COLOR,
+                            custom_size: Some(FORCE_FIELD_MARKER_SIZE),
+                            ..default()
+                        },
+                        ..default()
+                    });
+                }
+            });
+            force_field
+        }
+        ForceFieldKind::Wind { size, acceleration } => {
+            let mut force_field = commands.spawn(MaterialMesh2dBundle {
+                mesh: meshes.add(shape::Quad::new(size).into()).into(),
+                material,
+                transform,
+                ..default()
+            });
+            force_field.with_children(|parent| {
+                // a grid of markers pointing the way balls get pushed
+                let rotation = Quat::from_rotation_z(acceleration.y.atan2(acceleration.x));
+                let markers = (size / WIND_MARKER_SPACING).floor().max(Vec2::ONE);
+                for x in 0..markers.x as usize {
+                    for y in 0..markers.y as usize {
+                        let position =
+                            (Vec2::new(x as f32, y as f32) + 0.5) / markers * size - (size / 2.0);
+                        parent.spawn(SpriteBundle {
+                            transform: Transform::from_translation(position.extend(0.001))
+                                .with_rotation(rotation),
+                            sprite: Sprite {
+                                color: FORCE_FIELD_MARKER_COLOR,
+                                custom_size: Some(FORCE_FIELD_MARKER_SIZE),
+                                ..default()
+                            },
+                            ..default()
+                        });
+                    }
+                }
+            });
+            force_field
+        }
+    };
+
+    force_field.insert(force_field_settings.kind.clone());
+
+    force_field
 }
 
 /// Determines what color the provided score area should be
@@ -1114,6 +3459,7 @@ pub fn spawn_player_shape<'w, 's, 'a>(
     meshes: &mut Assets<Mesh>,
     materials: &mut Assets<ColorMaterial>,
     image_assets: &ImageAssets,
+    player: Player,
     configured_sides: &ConfiguredSides,
     transform: Transform,
 ) -> EntityCommands<'w, 's, 'a> {
@@ -1133,12 +3479,7 @@ pub fn spawn_player_shape<'w, 's, 'a>(
         mesh: meshes
             .add(shape::RegularPolygon::new(PLAYER_SHAPE_RADIUS, PLAYER_SHAPE_SIDES).into())
             .into(),
-        material: materials.add(ColorMaterial::from(Color::Rgba {
-            red: 1.0,
-            green: 1.0,
-            blue: 1.0,
-            alpha: 0.01,
-        })),
+        material: materials.add(ColorMaterial::from(player.shape_color())),
         transform,
         ..default()
     });
@@ -1158,11 +3499,13 @@ pub fn spawn_player_shape<'w, 's, 'a>(
         })
         .insert(GravityScale(0.0))
         .insert(PlayerShape)
+        .insert(player)
         .with_children(|parent| {
             // side 0
             let side_0_type = configured_sides.get(&SideId(0));
             spawn_side(parent, side_0_type, side_sprite_custom_size, image_assets)
                 .insert(SideId(0))
+                .insert(player)
                 .insert(side_collider.clone())
                 .insert(
                     Transform::from_translation(Vec3::new(
@@ -1177,6 +3520,7 @@ pub fn spawn_player_shape<'w, 's, 'a>(
             let side_1_type = configured_sides.get(&SideId(1));
             spawn_side(parent, side_1_type, side_sprite_custom_size, image_assets)
                 .insert(SideId(1))
+                .insert(player)
                 .insert(side_collider.clone())
                 .insert(
                     Transform::from_translation(Vec3::new(
@@ -1191,6 +3535,7 @@ pub fn spawn_player_shape<'w, 's, 'a>(
             let side_2_type = configured_sides.get(&SideId(2));
             spawn_side(parent, side_2_type, side_sprite_custom_size, image_assets)
                 .insert(SideId(2))
+                .insert(player)
                 .insert(side_collider.clone())
                 .insert(
                     Transform::from_translation(Vec3::new(
@@ -1205,6 +3550,7 @@ pub fn spawn_player_shape<'w, 's, 'a>(
             let side_3_type = configured_sides.get(&SideId(3));
             spawn_side(parent, side_3_type, side_sprite_custom_size, image_assets)
                 .insert(SideId(3))
+                .insert(player)
                 .insert(side_collider.clone())
                 .insert(
                     Transform::from_translation(Vec3::new(
@@ -1339,12 +3685,11 @@ fn spawn_side<'w, 's, 'a>(
     side
 }
 
-struct SpawnTime(Instant);
-
-impl Default for SpawnTime {
-    fn default() -> Self {
-        Self(Instant::now())
-    }
+/// When to spawn the next ball. This starts over with each level, so the same seed always spawns the same balls at the same times.
+#[derive(Resource)]
+struct SpawnTimer {
+    next_spawn_time: Instant,
+    balls_spawned_in_group: u32,
 }
 
 /// Spawns balls
@@ -1356,109 +3701,148 @@ fn spawn_balls(
     level_settings: Res<LevelSettings>,
     balls_query: Query<&Ball>,
     color_blind_mode: Res<ColorBlindMode>,
-    mut next_spawn_time: Local<SpawnTime>,
-    mut balls_spawned_in_group: Local<u32>,
-    audio_assets: Res<AudioAssets>,
-    audio: Res<Audio>,
+    mut spawn_timer: ResMut<SpawnTimer>,
+    mut sound_events: EventWriter<SoundEvent>,
+    mut rng: ResMut<LevelRng>,
+    time: Res<Time>,
 ) {
     if balls_query.is_empty()
-        && next_spawn_time.0.saturating_duration_since(Instant::now())
+        && spawn_timer
+            .next_spawn_time
+            .saturating_duration_since(now(&time))
             > level_settings.max_respite_time
     {
         // there are no balls left on screen, so reduce time until next group is spawned
-        next_spawn_time.0 = Instant::now() + level_settings.max_respite_time;
-    } else if Instant::now().saturating_duration_since(next_spawn_time.0) > Duration::ZERO {
-        spawn_random_ball(
+        spawn_timer.next_spawn_time = now(&time) + level_settings.max_respite_time;
+    } else if now(&time).saturating_duration_since(spawn_timer.next_spawn_time) > Duration::ZERO {
+        let spawn_position = spawn_random_ball(
             commands,
             &color_blind_mode,
             meshes,
             materials,
             &level_settings,
+            &mut rng.0,
+            now(&time),
         );
 
-        audio.play_with_settings(
-            audio_assets.launch.clone(),
-            PlaybackSettings::ONCE.with_volume(SPAWN_SOUND_VOLUME * MASTER_VOLUME),
-        );
+        sound_events.send(SoundEvent::new(SoundKind::BallSpawned, spawn_position));
 
-        *balls_spawned_in_group += 1;
+        spawn_timer.balls_spawned_in_group += 1;
 
-        if *balls_spawned_in_group >= level_settings.balls_per_group {
-            *balls_spawned_in_group = 0;
-            next_spawn_time.0 = Instant::now() + level_settings.time_between_groups;
+        if spawn_timer.balls_spawned_in_group >= level_settings.balls_per_group {
+            spawn_timer.balls_spawned_in_group = 0;
+            spawn_timer.next_spawn_time = now(&time) + level_settings.time_between_groups;
         } else {
-            next_spawn_time.0 = Instant::now() + level_settings.time_between_spawns_in_group;
+            spawn_timer.next_spawn_time = now(&time) + level_settings.time_between_spawns_in_group;
         }
     }
 }
 
-/// Spawns a random ball at a random point with a random initial impulse
+/// Spawns a random ball at a random point with a random initial impulse, and returns where it was spawned
 fn spawn_random_ball(
     mut commands: Commands,
     color_blind_mode: &ColorBlindMode,
     mut meshes: ResMut<Assets<Mesh>>,
     mut materials: ResMut<Assets<ColorMaterial>>,
     level_settings: &LevelSettings,
-) {
-    let mut rng = rand::thread_rng();
-    let ball_type = BallType::random(level_settings, &mut rng);
+    rng: &mut ChaCha8Rng,
+    now: Instant,
+) -> Vec2 {
+    let ball_type = BallType::random(level_settings, rng);
     let spawn_point = level_settings
         .spawn_points
-        .choose(&mut rng)
+        .choose(rng)
         .expect("at least one spawn point should be defined");
     let spawn_point_x = rng.gen_range(spawn_point.start_position_range_x.clone());
     let spawn_point_y = rng.gen_range(spawn_point.start_position_range_y.clone());
     let impulse_x = rng.gen_range(spawn_point.start_impulse_range_x.clone());
     let impulse_y = rng.gen_range(spawn_point.start_impulse_range_y.clone());
-    spawn_ball(
+    let chameleon = rng.gen_bool(level_settings.chameleon_ball_chance);
+    let ball_properties = level_settings
+        .ball_properties
+        .for_ball(ball_type, chameleon);
+
+    // keep big balls from spawning inside the walls
+    let spawn_position = level_settings.arena.clamp_inside(
+        Vec2::new(spawn_point_x, spawn_point_y),
+        ball_properties.radius + 1.0,
+    );
+
+    let mut ball = spawn_ball(
         &mut commands,
         Ball {
             ball_type,
             points: 1,
         },
+        ball_properties,
+        level_settings.ball_collision_restitution.is_some(),
         color_blind_mode,
         &mut meshes,
         &mut materials,
-    )
-    .insert(TransformBundle::from(Transform::from_xyz(
-        spawn_point_x,
-        spawn_point_y,
-        0.0,
+    );
+
+    ball.insert(TransformBundle::from(Transform::from_translation(
+        spawn_position.extend(0.0),
     )))
     .insert(ExternalImpulse {
         impulse: Vec2::new(impulse_x, impulse_y),
         ..default()
     });
+
+    if chameleon {
+        ball.insert(ChameleonBall {
+            next_change_at: now + CHAMELEON_CHANGE_INTERVAL,
+        });
+    }
+
+    spawn_position
 }
 
 /// Spawns a ball
 fn spawn_ball<'w, 's, 'a>(
     commands: &'a mut Commands<'w, 's>,
     ball_component: Ball,
+    ball_properties: &BallProperties,
+    balls_collide: bool,
     color_blind_mode: &ColorBlindMode,
     meshes: &mut Assets<Mesh>,
     materials: &mut Assets<ColorMaterial>,
 ) -> EntityCommands<'w, 's, 'a> {
     let mut ball = commands.spawn(RigidBody::Dynamic);
 
-    ball.insert(Collider::ball(BALL_SIZE))
+    if balls_collide {
+        ball.insert(CollisionGroups::new(BALL_COLLISION_GROUP, Group::all()))
+            .insert(ActiveHooks::MODIFY_SOLVER_CONTACTS);
+    } else {
         // make balls go through each other
-        .insert(CollisionGroups::new(
+        ball.insert(CollisionGroups::new(
             BALL_COLLISION_GROUP,
             Group::all().difference(BALL_COLLISION_GROUP),
-        ))
+        ));
+    }
+
+    ball.insert(Collider::ball(ball_properties.radius))
+        .insert(ColliderMassProperties::Mass(ball_properties.mass))
         .insert(MaterialMesh2dBundle {
-            mesh: meshes.add(shape::Circle::new(BALL_SIZE).into()).into(),
+            mesh: meshes
+                .add(shape::Circle::new(ball_properties.radius).into())
+                .into(),
             material: materials.add(ColorMaterial::from(
                 ball_component.ball_type.color(color_blind_mode),
             )),
             ..default()
         })
         .insert(Restitution {
-            coefficient: 1.0,
+            coefficient: ball_properties.restitution,
             combine_rule: CoefficientCombineRule::Multiply,
         })
+        .insert(Damping {
+            linear_damping: ball_properties.linear_damping,
+            angular_damping: 0.0,
+        })
         .insert(Velocity::zero())
+        .insert(ExternalForce::default())
+        .insert(ReadMassProperties::default())
         .insert(ActiveEvents::COLLISION_EVENTS)
         .insert(Sleeping::disabled())
         .insert(GameComponent)
@@ -1467,44 +3851,225 @@ fn spawn_ball<'w, 's, 'a>(
     ball
 }
 
-/// Applies impulses to the player based on pressed keys
+type HumanPlayerShapeTuple = (With<PlayerShape>, Without<Bot>);
+
+/// Applies impulses to the players based on pressed keys and gamepad input
+#[allow(clippy::too_many_arguments)]
 fn player_movement(
-    mut player_shape_query: Query<(&mut ExternalForce, &mut ExternalImpulse), With<PlayerShape>>,
+    mut player_shape_query: Query<
+        (&Player, &mut ExternalForce, &mut ExternalImpulse),
+        HumanPlayerShapeTuple,
+    >,
     keycode: Res<Input<KeyCode>>,
+    gamepads: Res<Gamepads>,
+    gamepad_axes: Res<Axis<GamepadAxis>>,
+    gamepad_buttons: Res<Input<GamepadButton>>,
     mut scroll_events: EventReader<MouseWheel>,
     rotate_sensitivity: Res<RotateSensitivity>,
+    two_players: Res<TwoPlayers>,
+    bot_players: Res<BotPlayers>,
 ) {
-    for (mut force, mut impulse) in &mut player_shape_query {
+    // when the other player is a bot, the human player gets the whole keyboard to themselves
+    let split_keyboard = two_players.0 && bot_players.0.is_empty();
+    let gamepad = gamepads.iter().next();
+    let scroll = scroll_events
+        .iter()
+        .last()
+        .map(|event| event.y.clamp(-1.0, 1.0));
+
+    for (player, mut force, mut impulse) in &mut player_shape_query {
+        let (movement, rotation) = match (split_keyboard, player, gamepad) {
+            (false, _, _) | (true, Player::One, Some(_)) => SOLO_KEY_BINDINGS.input(&keycode),
+            (true, Player::One, None) => PLAYER_ONE_SPLIT_KEY_BINDINGS.input(&keycode),
+            (true, Player::Two, None) => PLAYER_TWO_SPLIT_KEY_BINDINGS.input(&keycode),
+            (true, Player::Two, Some(gamepad)) => {
+                gamepad_input(gamepad, &gamepad_axes, &gamepad_buttons)
+            }
+        };
+
         // translation
-        if keycode.pressed(MOVE_LEFT_KEY) {
-            force.force.x = -MOVE_SPEED;
-        } else if keycode.pressed(MOVE_RIGHT_KEY) {
-            force.force.x = MOVE_SPEED;
+        force.force = movement * MOVE_SPEED;
+
+        // rotation
+        force.torque = rotation * ROTATE_SPEED * rotate_sensitivity.0;
+
+        if *player == Player::One {
+            if let Some(scroll) = scroll {
+                impulse.torque_impulse = scroll * SCROLL_ROTATE_SPEED * rotate_sensitivity.0;
+            }
+        }
+    }
+}
+
+/// Gets the direction of movement and rotation from the provided gamepad. Positive rotation is counterclockwise.
+fn gamepad_input(
+    gamepad: Gamepad,
+    gamepad_axes: &Axis<GamepadAxis>,
+    gamepad_buttons: &Input<GamepadButton>,
+) -> (Vec2, f32) {
+    let axis = |axis_type| {
+        let value = gamepad_axes
+            .get(GamepadAxis::new(gamepad, axis_type))
+            .unwrap_or(0.0);
+        if value.abs() < GAMEPAD_DEADZONE {
+            0.0
         } else {
-            force.force.x = 0.0;
+            value
         }
+    };
+
+    let movement = Vec2::new(
+        axis(GamepadAxisType::LeftStickX),
+        axis(GamepadAxisType::LeftStickY),
+    );
+
+    let rotation = if gamepad_buttons.pressed(GamepadButton::new(
+        gamepad,
+        GamepadButtonType::RightTrigger2,
+    )) {
+        -1.0
+    } else if gamepad_buttons.pressed(GamepadButton::new(gamepad, GamepadButtonType::LeftTrigger2))
+    {
+        1.0
+    } else {
+        -axis(GamepadAxisType::RightStickX)
+    };
+
+    (movement, rotation)
+}
+
+type BotPlayerShapeTuple = (With<PlayerShape>, With<Bot>);
+
+/// Applies forces to bot-controlled players to go after balls
+fn bot_movement(
+    mut bot_query: Query<(Entity, &Transform, &mut ExternalForce), BotPlayerShapeTuple>,
+    sides_query: Query<(&Parent, &SideType, &Transform), With<SideId>>,
+    balls_query: Query<(&Ball, &Transform, &Velocity)>,
+    score_areas_query: Query<(&ScoreArea, &Transform)>,
+) {
+    let balls = balls_query
+        .iter()
+        .map(|(ball, transform, velocity)| {
+            (
+                ball.ball_type,
+                transform.translation.truncate(),
+                velocity.linvel,
+            )
+        })
+        .collect::<Vec<_>>();
+    let score_areas = score_areas_query
+        .iter()
+        .map(|(score_area, transform)| (score_area.0, transform.translation.truncate()))
+        .collect::<Vec<_>>();
+
+    for (entity, transform, mut force) in &mut bot_query {
+        let sides = sides_query
+            .iter()
+            .filter(|(parent, _, _)| parent.get() == entity)
+            .map(|(_, side_type, side_transform)| {
+                (
+                    side_transform.translation.truncate().normalize(),
+                    *side_type,
+                )
+            })
+            .collect::<Vec<_>>();
+
+        let (movement, rotation) = bot_input(transform, &sides, &balls, &score_areas);
+        force.force = movement * MOVE_SPEED;
+        force.torque = rotation * ROTATE_SPEED;
+    }
+}
+
+/// Decides how a bot-controlled player shape should move and rotate, in the same terms as player input.
+/// Positive rotation is counterclockwise.
+///
+/// `sides` are the outward directions of each side relative to the player shape, along with their types.
+/// `balls` are the types, positions, and velocities of the balls in play.
+/// `score_areas` are the types and positions of the score areas.
+fn bot_input(
+    shape_transform: &Transform,
+    sides: &[(Vec2, SideType)],
+    balls: &[(BallType, Vec2, Vec2)],
+    score_areas: &[(BallType, Vec2)],
+) -> (Vec2, f32) {
+    let position = shape_transform.translation.truncate();
+
+    // go after the ball that will be closest soon
+    let target = balls
+        .iter()
+        .filter_map(|(ball_type, ball_position, ball_velocity)| {
+            let predicted_position = *ball_position + *ball_velocity * BOT_LOOKAHEAD;
+            let score_area_position = score_areas
+                .iter()
+                .filter(|(score_area_type, _)| score_area_type == ball_type)
+                .map(|(_, score_area_position)| *score_area_position)
+                .min_by(|a, b| {
+                    a.distance_squared(predicted_position)
+                        .total_cmp(&b.distance_squared(predicted_position))
+                })?;
+            Some((predicted_position, score_area_position))
+        })
+        .min_by(|(a, _), (b, _)| {
+            a.distance_squared(position)
+                .total_cmp(&b.distance_squared(position))
+        });
+
+    let Some((ball_position, score_area_position)) = target else {
+        // nothing to do, so wait in the middle
+        let movement = (-position / BOT_SLOW_DOWN_DISTANCE).clamp_length_max(1.0);
+        return (movement, 0.0);
+    };
 
-        if keycode.pressed(MOVE_UP_KEY) {
-            force.force.y = MOVE_SPEED;
-        } else if keycode.pressed(MOVE_DOWN_KEY) {
-            force.force.y = -MOVE_SPEED;
+    let push_direction = (score_area_position - ball_position).normalize_or_zero();
+
+    // face the most useful side towards where the ball should go
+    let world_normal =
+        |local_normal: Vec2| (shape_transform.rotation * local_normal.extend(0.0)).truncate();
+    let angle_error = |local_normal: Vec2| {
+        let current_angle = world_normal(local_normal).angle_between(push_direction);
+        if current_angle.is_nan() {
+            0.0
         } else {
-            force.force.y = 0.0;
+            current_angle
         }
+    };
+    let rotation_error = sides
+        .iter()
+        .max_by(|(a_normal, a_type), (b_normal, b_type)| {
+            bot_side_preference(a_type)
+                .cmp(&bot_side_preference(b_type))
+                .then_with(|| {
+                    angle_error(*b_normal)
+                        .abs()
+                        .total_cmp(&angle_error(*a_normal).abs())
+                })
+        })
+        .map(|(normal, _)| angle_error(*normal))
+        .unwrap_or(0.0);
+    let rotation = (rotation_error * BOT_ROTATE_GAIN).clamp(-1.0, 1.0);
+
+    // line up behind the ball, then push it once facing the right way
+    let behind_ball = (ball_position - position).dot(push_direction) > 0.0;
+    let move_target = if behind_ball && rotation_error.abs() < BOT_MAX_PUSH_ANGLE {
+        ball_position
+    } else {
+        ball_position - push_direction * BOT_LINE_UP_DISTANCE
+    };
+    let movement = ((move_target - position) / BOT_SLOW_DOWN_DISTANCE).clamp_length_max(1.0);
 
-        // rotation
-        if keycode.pressed(ROTATE_CLOCKWISE_KEY) {
-            force.torque = -ROTATE_SPEED * rotate_sensitivity.0;
-        } else if keycode.pressed(ROTATE_COUNTERCLOCKWISE_KEY) {
-            force.torque = ROTATE_SPEED * rotate_sensitivity.0;
-        } else {
-            force.torque = 0.0;
-        }
+    (movement, rotation)
+}
 
-        for event in scroll_events.iter() {
-            impulse.torque_impulse =
-                event.y.clamp(-1.0, 1.0) * SCROLL_ROTATE_SPEED * rotate_sensitivity.0;
-        }
+/// Determines how much bots like to hit balls with the provided type of side
+fn bot_side_preference(side_type: &SideType) -> i32 {
+    match side_type {
+        SideType::ExtraPoints => 5,
+        SideType::Duplicate => 4,
+        SideType::SpeedUp | SideType::ExtremeBounce => 3,
+        SideType::NothingSpecial | SideType::ResizeScoreAreas => 2,
+        SideType::FreezeOthers => 1,
+        SideType::BounceBackwards => 0,
+        SideType::Destroy => -1,
     }
 }
 
@@ -1560,41 +4125,69 @@ fn collisions(
     mut collision_events: EventReader<CollisionEvent>,
     mut score: ResMut<Score>,
     mut entities_to_despawn: ResMut<EntitiesToDespawn>,
-    audio: Res<Audio>,
-    audio_assets: Res<AudioAssets>,
-    balls_query: Query<&Ball>,
-    score_areas_query: Query<(&ScoreArea, Option<&Resized>)>,
-    sides_query: Query<(&SideType, &SideId)>,
+    mut sound_events: EventWriter<SoundEvent>,
+    balls_query: Query<(&Ball, &Transform)>,
+    score_areas_query: Query<(&ScoreArea, Option<&Resized>, Option<&ScoreAreaOwner>)>,
+    sides_query: Query<(&SideType, &SideId, &Player)>,
+    obstacles_query: Query<(&ObstacleKind, &ObstacleSound, &Transform)>,
+    portals_query: Query<&Portal>,
+    portal_cooldowns_query: Query<&PortalCooldown>,
+    game_mode: Res<GameMode>,
+    (mut lives, mut versus_scores): (ResMut<Lives>, ResMut<VersusScores>),
+    mut side_effect_counts: ResMut<SideEffectCounts>,
+    time: Res<Time>,
 ) {
     for event in collision_events.iter() {
         if let CollisionEvent::Started(a, b, _) = event {
-            if let Some((ball, ball_entity)) = get_from_either::<Ball, &Ball>(*a, *b, &balls_query)
+            if let Some((ball, ball_entity)) =
+                get_from_either::<Ball, (&Ball, &Transform)>(*a, *b, &balls_query)
             {
                 // a ball has hit something
                 if entities_to_despawn.0.contains(&ball_entity) {
                     // this ball is going to be despawned, so don't mess with it any more
                     continue;
                 }
+                let ball_position = balls_query
+                    .get_component::<Transform>(ball_entity)
+                    .expect("ball should have a transform")
+                    .translation
+                    .truncate();
                 unfreeze_entity(ball_entity, &mut commands);
-                if let Some((score_area, score_area_entity)) = get_from_either::<
-                    ScoreArea,
-                    (&ScoreArea, Option<&Resized>),
-                >(
-                    *a, *b, &score_areas_query
-                ) {
+                if balls_query.contains(*a) && balls_query.contains(*b) {
+                    // two balls have hit each other
+                    let other_ball_entity = if *a == ball_entity { *b } else { *a };
+                    unfreeze_entity(other_ball_entity, &mut commands);
+                    sound_events.send(SoundEvent::new(SoundKind::BallHitBall, ball_position));
+                    continue;
+                }
+                if let Some((score_area, score_area_entity)) =
+                    get_from_either::<
+                        ScoreArea,
+                        (&ScoreArea, Option<&Resized>, Option<&ScoreAreaOwner>),
+                    >(*a, *b, &score_areas_query)
+                {
                     // a ball has hit a score area
+                    let owner = score_areas_query
+                        .get_component::<ScoreAreaOwner>(score_area_entity)
+                        .ok()
+                        .filter(|_| *game_mode == GameMode::Versus)
+                        .map(|owner| owner.0);
                     if ball.ball_type == score_area.0 {
-                        score.0 += i32::from(ball.points);
+                        if let Some(owner) = owner {
+                            *versus_scores.0.entry(owner).or_default() += i32::from(ball.points);
+                        } else {
+                            score.0 += i32::from(ball.points);
+                        }
                         commands
                             .entity(score_area_entity)
                             .insert(AnimateScoreAreaHit {
                                 score_change: i32::from(ball.points),
-                                hit_time: Instant::now(),
+                                hit_time: now(&time),
                             });
-                        audio.play_with_settings(
-                            audio_assets.good.clone(),
-                            PlaybackSettings::ONCE.with_volume(GOOD_SCORE_VOLUME * MASTER_VOLUME),
-                        );
+                        sound_events.send(SoundEvent::new(
+                            SoundKind::BallScored { correct: true },
+                            ball_position,
+                        ));
                     } else {
                         if let Ok(resized) =
                             score_areas_query.get_component::<Resized>(score_area_entity)
@@ -1604,33 +4197,87 @@ fn collisions(
                                 continue;
                             }
                         }
-                        score.0 -= i32::from(ball.points);
+                        if let Some(owner) = owner {
+                            // wrong colors in a player's score area cost their opponent points in versus mode
+                            *versus_scores.0.entry(owner.opponent()).or_default() -=
+                                i32::from(ball.points);
+                        } else if *game_mode == GameMode::Endless {
+                            // wrong colors cost a life in endless mode instead of points
+                            lives.0 = lives.0.saturating_sub(1);
+                        } else {
+                            score.0 -= i32::from(ball.points);
+                        }
                         commands
                             .entity(score_area_entity)
                             .insert(AnimateScoreAreaHit {
                                 score_change: -i32::from(ball.points),
-                                hit_time: Instant::now(),
+                                hit_time: now(&time),
                             });
-                        audio.play_with_settings(
-                            audio_assets.bad.clone(),
-                            PlaybackSettings::ONCE.with_volume(BAD_SCORE_VOLUME * MASTER_VOLUME),
-                        );
+                        sound_events.send(SoundEvent::new(
+                            SoundKind::BallScored { correct: false },
+                            ball_position,
+                        ));
                     }
                     entities_to_despawn.0.push(ball_entity);
                 } else {
                     // a ball has hit something that's not a score area
-                    audio.play_with_settings(
-                        audio_assets.hit.clone(),
-                        PlaybackSettings::ONCE.with_volume(HIT_SOUND_VOLUME * MASTER_VOLUME),
-                    );
-
-                    if let Some((side_type, side_entity)) =
-                        get_from_either::<SideType, (&SideType, &SideId)>(*a, *b, &sides_query)
+                    if let Some((portal, _)) =
+                        get_from_either::<Portal, &Portal>(*a, *b, &portals_query)
                     {
-                        if let Ok(side_id) = sides_query.get_component::<SideId>(side_entity) {
+                        // a ball has entered a portal
+                        if !portal_cooldowns_query.contains(ball_entity) {
+                            commands
+                                .entity(ball_entity)
+                                .insert(PortalEffect { exit: portal.exit });
+                        }
+                        continue;
+                    }
+
+                    if let Some((obstacle_kind, obstacle_entity)) = get_from_either::<
+                        ObstacleKind,
+                        (&ObstacleKind, &ObstacleSound, &Transform),
+                    >(
+                        *a, *b, &obstacles_query
+                    ) {
+                        // a ball has hit an obstacle
+                        if let Ok((_, sound, transform)) = obstacles_query.get(obstacle_entity) {
+                            sound_events.send(SoundEvent::new(
+                                SoundKind::BallHitObstacle(*sound),
+                                ball_position,
+                            ));
+
+                            if let ObstacleKind::Bumper { impulse, .. } = obstacle_kind {
+                                commands.entity(ball_entity).insert(BumpEffect {
+                                    bumper_position: transform.translation.truncate(),
+                                    impulse: *impulse,
+                                });
+                            }
+                        }
+                        continue;
+                    }
+
+                    if let Some((side_type, side_entity)) = get_from_either::<
+                        SideType,
+                        (&SideType, &SideId, &Player),
+                    >(
+                        *a, *b, &sides_query
+                    ) {
+                        if let Ok((_, side_id, player)) = sides_query.get(side_entity) {
                             // a ball has hit a side
-                            side_type.add_side_effect(ball_entity, *side_id, &mut commands);
+                            sound_events.send(SoundEvent::new(
+                                SoundKind::BallHitSide(*side_type),
+                                ball_position,
+                            ));
+                            *side_effect_counts.0.entry(*side_type).or_default() += 1;
+                            side_type.add_side_effect(
+                                ball_entity,
+                                *player,
+                                *side_id,
+                                &mut commands,
+                            );
                         }
+                    } else {
+                        sound_events.send(SoundEvent::new(SoundKind::BallHitWall, ball_position));
                     }
                 }
             }
@@ -1638,6 +4285,11 @@ fn collisions(
     }
 }
 
+/// Gets the time the current frame started. Game logic uses this instead of `Instant::now()` so it can be run faster than real time.
+fn now(time: &Time) -> Instant {
+    time.last_update().unwrap_or_else(Instant::now)
+}
+
 fn get_from_either<'a, T: Component, Q: ReadOnlyWorldQuery>(
     a: Entity,
     b: Entity,
@@ -1657,50 +4309,50 @@ fn get_from_either<'a, T: Component, Q: ReadOnlyWorldQuery>(
 /// Deals with entities that have had the speed up effect added
 fn handle_speed_up_effect(
     mut commands: Commands,
-    query: Query<Entity, Added<SpeedUpEffect>>,
-    audio: Res<Audio>,
-    audio_assets: Res<AudioAssets>,
+    query: Query<(Entity, &Transform), Added<SpeedUpEffect>>,
+    mut sound_events: EventWriter<SoundEvent>,
 ) {
-    for entity in query.iter() {
-        audio.play_with_settings(
-            audio_assets.up.clone(),
-            PlaybackSettings::ONCE.with_volume(0.75 * MASTER_VOLUME),
-        );
+    for (entity, transform) in query.iter() {
+        sound_events.send(SoundEvent::new(
+            SoundKind::SideEffect(SideType::SpeedUp),
+            transform.translation.truncate(),
+        ));
         commands.entity(entity).remove::<SpeedUpEffect>();
     }
 }
 
 /// Deals with entities that have had the freeze others effect added
+#[allow(clippy::too_many_arguments)]
 fn handle_freeze_others_effect(
     mut commands: Commands,
-    query: Query<Entity, Added<FreezeOthersEffect>>,
+    query: Query<(Entity, &Transform), Added<FreezeOthersEffect>>,
     mut frozen_query: Query<&mut Frozen>,
     balls_query: Query<(Entity, &Velocity), With<Ball>>,
-    audio: Res<Audio>,
-    audio_assets: Res<AudioAssets>,
+    mut sound_events: EventWriter<SoundEvent>,
+    time: Res<Time>,
 ) {
-    for entity in query.iter() {
+    for (entity, transform) in query.iter() {
         for (ball_entity, velocity) in balls_query.iter() {
             if ball_entity != entity {
                 if let Ok(mut frozen) = frozen_query.get_mut(ball_entity) {
                     // the ball is already frozen, so just update its unfreeze time
-                    frozen.unfreeze_at = Instant::now() + FREEZE_DURATION;
+                    frozen.unfreeze_at = now(&time) + FREEZE_DURATION;
                 } else {
                     // the ball is not currently frozen, so freeze it
                     commands
                         .entity(ball_entity)
                         .insert(Frozen {
-                            unfreeze_at: Instant::now() + FREEZE_DURATION,
+                            unfreeze_at: now(&time) + FREEZE_DURATION,
                             original_velocity: *velocity,
                         })
                         .insert(RigidBody::Fixed);
                 }
             }
         }
-        audio.play_with_settings(
-            audio_assets.down.clone(),
-            PlaybackSettings::ONCE.with_volume(1.0 * MASTER_VOLUME),
-        );
+        sound_events.send(SoundEvent::new(
+            SoundKind::SideEffect(SideType::FreezeOthers),
+            transform.translation.truncate(),
+        ));
         commands.entity(entity).remove::<FreezeOthersEffect>();
     }
 }
@@ -1714,38 +4366,44 @@ fn handle_bounce_backwards_effect(
         (
             Entity,
             &BounceBackwardsEffect,
+            &Collider,
             &mut Transform,
             &mut Velocity,
         ),
         AddedBounceBackwardsEffectTuple,
     >,
-    sides_query: Query<(&SideId, &GlobalTransform)>,
-    audio: Res<Audio>,
-    audio_assets: Res<AudioAssets>,
+    sides_query: Query<(&Player, &SideId, &GlobalTransform)>,
+    mut sound_events: EventWriter<SoundEvent>,
 ) {
     let sides = sides_query
         .iter()
-        .collect::<HashMap<&SideId, &GlobalTransform>>();
-    for (entity, bounce_backwards_effect, mut transform, mut velocity) in query.iter_mut() {
+        .map(|(player, side_id, transform)| ((*player, *side_id), transform))
+        .collect::<HashMap<(Player, SideId), &GlobalTransform>>();
+    for (entity, bounce_backwards_effect, collider, mut transform, mut velocity) in query.iter_mut()
+    {
         let hit_side_transform = sides
-            .get(&bounce_backwards_effect.side_hit)
+            .get(&(
+                bounce_backwards_effect.player,
+                bounce_backwards_effect.side_hit,
+            ))
             .expect("hit side should have a transform");
 
         let opposide_side_id = bounce_backwards_effect.side_hit.opposite_side();
         let opposite_side_transform = sides
-            .get(&opposide_side_id)
+            .get(&(bounce_backwards_effect.player, opposide_side_id))
             .expect("opposite side should have a transform");
 
         let direction =
             (opposite_side_transform.translation() - hit_side_transform.translation()).normalize();
         velocity.linvel = (BOUNCE_BACKWARDS_VELOCITY * direction).truncate();
-        transform.translation =
-            opposite_side_transform.translation() + (direction * BOUNCE_BACKWARDS_DISTANCE);
-
-        audio.play_with_settings(
-            audio_assets.boop.clone(),
-            PlaybackSettings::ONCE.with_volume(0.33 * MASTER_VOLUME),
-        );
+        let ball_radius = collider.as_ball().map_or(BALL_SIZE, |ball| ball.radius());
+        transform.translation = opposite_side_transform.translation()
+            + (direction * (ball_radius + BOUNCE_BACKWARDS_GAP));
+
+        sound_events.send(SoundEvent::new(
+            SoundKind::SideEffect(SideType::BounceBackwards),
+            transform.translation.truncate(),
+        ));
         commands.entity(entity).remove::<BounceBackwardsEffect>();
     }
 }
@@ -1753,18 +4411,17 @@ fn handle_bounce_backwards_effect(
 /// Deals with entities that have had the destroy effect added
 fn handle_destroy_effect(
     mut commands: Commands,
-    query: Query<Entity, Added<DestroyEffect>>,
+    query: Query<(Entity, &Transform), Added<DestroyEffect>>,
     mut entities_to_despawn: ResMut<EntitiesToDespawn>,
-    audio: Res<Audio>,
-    audio_assets: Res<AudioAssets>,
+    mut sound_events: EventWriter<SoundEvent>,
 ) {
-    for entity in query.iter() {
+    for (entity, transform) in query.iter() {
         entities_to_despawn.0.push(entity);
 
-        audio.play_with_settings(
-            audio_assets.explode.clone(),
-            PlaybackSettings::ONCE.with_volume(0.33 * MASTER_VOLUME),
-        );
+        sound_events.send(SoundEvent::new(
+            SoundKind::SideEffect(SideType::Destroy),
+            transform.translation.truncate(),
+        ));
         commands.entity(entity).remove::<DestroyEffect>();
     }
 }
@@ -1775,20 +4432,31 @@ type EntityToDuplicateTuple<'a> = (
     &'a Transform,
     &'a Velocity,
     Option<&'a ExtraPointsEffect>,
+    Option<&'a ChameleonBall>,
     Option<&'a DuplicateCooldown>,
 );
 
 /// Deals with entities that have had the duplicate effect added
+#[allow(clippy::too_many_arguments)]
 fn handle_duplicate_effect(
     mut commands: Commands,
     query: Query<EntityToDuplicateTuple, Added<DuplicateEffect>>,
+    level_settings: Res<LevelSettings>,
     color_blind_mode: Res<ColorBlindMode>,
     mut meshes: ResMut<Assets<Mesh>>,
     mut materials: ResMut<Assets<ColorMaterial>>,
-    audio: Res<Audio>,
-    audio_assets: Res<AudioAssets>,
+    mut sound_events: EventWriter<SoundEvent>,
+    time: Res<Time>,
 ) {
-    for (entity, ball, transform, velocity, extra_points_effect, duplicate_cooldown) in query.iter()
+    for (
+        entity,
+        ball,
+        transform,
+        velocity,
+        extra_points_effect,
+        chameleon_ball,
+        duplicate_cooldown,
+    ) in query.iter()
     {
         if duplicate_cooldown.is_some() {
             commands.entity(entity).remove::<DuplicateEffect>();
@@ -1802,6 +4470,10 @@ fn handle_duplicate_effect(
                 ball_type: ball.ball_type,
                 points: 1,
             },
+            level_settings
+                .ball_properties
+                .for_ball(ball.ball_type, chameleon_ball.is_some()),
+            level_settings.ball_collision_restitution.is_some(),
             &color_blind_mode,
             &mut meshes,
             &mut materials,
@@ -1815,74 +4487,87 @@ fn handle_duplicate_effect(
                 ..default()
             })
             .insert(DuplicateCooldown {
-                remove_at: Instant::now() + DUPLICATE_COOLDOWN_DURATION,
+                remove_at: now(&time) + DUPLICATE_COOLDOWN_DURATION,
+                twin: entity,
             });
+        let new_ball_entity = new_ball.id();
 
         if let Some(extra_points_effect) = extra_points_effect {
             new_ball.insert(*extra_points_effect);
         }
 
-        audio.play_with_settings(
-            audio_assets.duplicate.clone(),
-            PlaybackSettings::ONCE.with_volume(0.4 * MASTER_VOLUME),
-        );
+        if let Some(chameleon_ball) = chameleon_ball {
+            new_ball.insert(chameleon_ball.clone());
+        }
+
+        sound_events.send(SoundEvent::new(
+            SoundKind::SideEffect(SideType::Duplicate),
+            transform.translation.truncate(),
+        ));
 
         commands
             .entity(entity)
             .remove::<DuplicateEffect>()
             .insert(DuplicateCooldown {
-                remove_at: Instant::now() + DUPLICATE_COOLDOWN_DURATION,
+                remove_at: now(&time) + DUPLICATE_COOLDOWN_DURATION,
+                twin: new_ball_entity,
             });
     }
 }
 
 /// Removes the duplication cooldown component from entities once the cooldown expires
-fn remove_duplicate_cooldown(mut commands: Commands, query: Query<(Entity, &DuplicateCooldown)>) {
+fn remove_duplicate_cooldown(
+    mut commands: Commands,
+    query: Query<(Entity, &DuplicateCooldown)>,
+    time: Res<Time>,
+) {
     for (entity, cooldown) in query.iter() {
-        if Instant::now().saturating_duration_since(cooldown.remove_at) > Duration::ZERO {
+        if now(&time).saturating_duration_since(cooldown.remove_at) > Duration::ZERO {
             commands.entity(entity).remove::<DuplicateCooldown>();
         }
     }
 }
 
 /// Deals with entities that have had the resize score areas effect added
+#[allow(clippy::too_many_arguments)]
 fn handle_resize_score_areas_effect(
     mut commands: Commands,
-    query: Query<(Entity, &Ball), Added<ResizeScoreAreasEffect>>,
-    mut score_areas_query: Query<(Entity, &ScoreArea, &mut Mesh2dHandle, &mut Collider)>,
+    query: Query<(Entity, &Ball, &Transform), Added<ResizeScoreAreasEffect>>,
+    mut score_areas_query: Query<(
+        Entity,
+        &ScoreArea,
+        &ScoreAreaShape,
+        &mut Mesh2dHandle,
+        &mut Collider,
+    )>,
     mut meshes: ResMut<Assets<Mesh>>,
-    audio: Res<Audio>,
-    audio_assets: Res<AudioAssets>,
+    mut sound_events: EventWriter<SoundEvent>,
+    time: Res<Time>,
 ) {
-    for (ball_entity, ball) in query.iter() {
-        for (score_area_entity, score_area, mut mesh, mut collider) in score_areas_query.iter_mut()
+    for (ball_entity, ball, ball_transform) in query.iter() {
+        for (score_area_entity, score_area, shape, mut mesh, mut collider) in
+            score_areas_query.iter_mut()
         {
             commands.entity(score_area_entity).insert(Resized {
-                unresize_at: Instant::now() + SCORE_AREA_RESIZE_DURATION,
-                original_mesh: meshes
-                    .add(shape::Circle::new(SCORE_AREA_SIZE).into())
-                    .into(),
-                original_collider: Collider::ball(SCORE_AREA_SIZE),
+                unresize_at: now(&time) + SCORE_AREA_RESIZE_DURATION,
+                original_mesh: meshes.add(shape.mesh()).into(),
+                original_collider: shape.collider(),
                 penalty_disabled: true,
             });
 
-            if ball.ball_type == score_area.0 {
-                *mesh = meshes
-                    .add(shape::Circle::new(SCORE_AREA_SIZE + SCORE_AREA_RESIZE_AMOUNT).into())
-                    .into();
-                *collider = Collider::ball(SCORE_AREA_SIZE + SCORE_AREA_RESIZE_AMOUNT);
+            let resized_shape = if ball.ball_type == score_area.0 {
+                shape.scaled(SCORE_AREA_GROW_FACTOR)
             } else {
-                *mesh = meshes
-                    .add(shape::Circle::new(SCORE_AREA_SIZE - SCORE_AREA_RESIZE_AMOUNT).into())
-                    .into();
-                *collider = Collider::ball(SCORE_AREA_SIZE - SCORE_AREA_RESIZE_AMOUNT);
-            }
+                shape.scaled(SCORE_AREA_SHRINK_FACTOR)
+            };
+            *mesh = meshes.add(resized_shape.mesh()).into();
+            *collider = resized_shape.collider();
         }
 
-        audio.play_with_settings(
-            audio_assets.resize.clone(),
-            PlaybackSettings::ONCE.with_volume(0.33 * MASTER_VOLUME),
-        );
+        sound_events.send(SoundEvent::new(
+            SoundKind::SideEffect(SideType::ResizeScoreAreas),
+            ball_transform.translation.truncate(),
+        ));
 
         commands
             .entity(ball_entity)
@@ -1893,37 +4578,149 @@ fn handle_resize_score_areas_effect(
 /// Deals with entities that have had the extreme bounce effect added
 fn handle_extreme_bounce_effect(
     mut commands: Commands,
-    query: Query<Entity, Added<ExtremeBounceEffect>>,
-    audio: Res<Audio>,
-    audio_assets: Res<AudioAssets>,
+    query: Query<(Entity, &Transform), Added<ExtremeBounceEffect>>,
+    mut sound_events: EventWriter<SoundEvent>,
 ) {
-    for entity in query.iter() {
-        audio.play_with_settings(
-            audio_assets.up_more.clone(),
-            PlaybackSettings::ONCE.with_volume(0.33 * MASTER_VOLUME),
-        );
+    for (entity, transform) in query.iter() {
+        sound_events.send(SoundEvent::new(
+            SoundKind::SideEffect(SideType::ExtremeBounce),
+            transform.translation.truncate(),
+        ));
         commands.entity(entity).remove::<ExtremeBounceEffect>();
     }
 }
 
-/// Deals with entities that have had the resize score areas effect added
+/// Deals with entities that have had the portal effect added
+fn handle_portal_effect(
+    mut commands: Commands,
+    mut query: Query<(Entity, &PortalEffect, &mut Transform, &mut Velocity), Added<PortalEffect>>,
+    portals_query: Query<&GlobalTransform, With<Portal>>,
+    mut sound_events: EventWriter<SoundEvent>,
+    time: Res<Time>,
+) {
+    for (entity, portal_effect, mut transform, mut velocity) in query.iter_mut() {
+        let exit_transform = portals_query
+            .get(portal_effect.exit)
+            .expect("exit portal should have a transform");
+
+        let (_, exit_rotation, exit_translation) = exit_transform.to_scale_rotation_translation();
+        let direction = (exit_rotation * Vec3::X).truncate();
+        velocity.linvel = direction * velocity.linvel.length();
+        transform.translation = exit_translation;
+
+        sound_events.send(SoundEvent::new(
+            SoundKind::BallTeleported,
+            transform.translation.truncate(),
+        ));
+        commands
+            .entity(entity)
+            .remove::<PortalEffect>()
+            .insert(PortalCooldown {
+                remove_at: now(&time) + PORTAL_COOLDOWN_DURATION,
+            });
+    }
+}
+
+/// Removes the portal cooldown component from entities once the cooldown expires
+fn remove_portal_cooldown(
+    mut commands: Commands,
+    query: Query<(Entity, &PortalCooldown)>,
+    time: Res<Time>,
+) {
+    for (entity, cooldown) in query.iter() {
+        if now(&time).saturating_duration_since(cooldown.remove_at) > Duration::ZERO {
+            commands.entity(entity).remove::<PortalCooldown>();
+        }
+    }
+}
+
+/// Deals with entities that have had the bump effect added
+fn handle_bump_effect(
+    mut commands: Commands,
+    mut query: Query<(Entity, &BumpEffect, &Transform, &mut ExternalImpulse), Added<BumpEffect>>,
+) {
+    for (entity, bump_effect, transform, mut impulse) in query.iter_mut() {
+        let direction =
+            (transform.translation.truncate() - bump_effect.bumper_position).normalize_or_zero();
+        impulse.impulse += direction * bump_effect.impulse;
+
+        commands.entity(entity).remove::<BumpEffect>();
+    }
+}
+
+type ExtraPointsBallTuple<'a> = (
+    &'a mut Ball,
+    &'a mut Mesh2dHandle,
+    &'a mut Collider,
+    &'a mut ColliderMassProperties,
+    &'a mut Restitution,
+    &'a mut Damping,
+    &'a Transform,
+);
+
+/// Deals with entities that have had the extra points effect added
 fn handle_extra_points_effect(
-    mut query: Query<(&mut Ball, &mut Mesh2dHandle, &mut Collider), Added<ExtraPointsEffect>>,
+    mut query: Query<ExtraPointsBallTuple, Added<ExtraPointsEffect>>,
+    level_settings: Res<LevelSettings>,
     mut meshes: ResMut<Assets<Mesh>>,
-    audio: Res<Audio>,
-    audio_assets: Res<AudioAssets>,
+    mut sound_events: EventWriter<SoundEvent>,
 ) {
-    for (mut ball, mut mesh, mut collider) in query.iter_mut() {
+    let ball_properties = &level_settings.ball_properties.extra_points;
+    for (
+        mut ball,
+        mut mesh,
+        mut collider,
+        mut mass_properties,
+        mut restitution,
+        mut damping,
+        transform,
+    ) in query.iter_mut()
+    {
         ball.points = 2;
         *mesh = meshes
-            .add(shape::Circle::new(EXTRA_POINT_BALL_SIZE).into())
+            .add(shape::Circle::new(ball_properties.radius).into())
             .into();
-        *collider = Collider::ball(EXTRA_POINT_BALL_SIZE);
+        *collider = Collider::ball(ball_properties.radius);
+        *mass_properties = ColliderMassProperties::Mass(ball_properties.mass);
+        restitution.coefficient = ball_properties.restitution;
+        damping.linear_damping = ball_properties.linear_damping;
+
+        sound_events.send(SoundEvent::new(
+            SoundKind::SideEffect(SideType::ExtraPoints),
+            transform.translation.truncate(),
+        ));
+    }
+}
 
-        audio.play_with_settings(
-            audio_assets.extra_points.clone(),
-            PlaybackSettings::ONCE.with_volume(0.66 * MASTER_VOLUME),
-        );
+/// Changes the types of chameleon balls when it's time, flashing them beforehand as a warning
+fn update_chameleon_balls(
+    level_settings: Res<LevelSettings>,
+    color_blind_mode: Res<ColorBlindMode>,
+    mut query: Query<(&mut Ball, &mut ChameleonBall, &Handle<ColorMaterial>)>,
+    mut materials: ResMut<Assets<ColorMaterial>>,
+    mut rng: ResMut<ChameleonRng>,
+    time: Res<Time>,
+) {
+    for (mut ball, mut chameleon_ball, material_handle) in query.iter_mut() {
+        let material = materials
+            .get_mut(material_handle)
+            .expect("material should exist");
+
+        let time_until_change = chameleon_ball
+            .next_change_at
+            .saturating_duration_since(now(&time));
+        if time_until_change == Duration::ZERO {
+            ball.ball_type = ball.ball_type.random_other(&level_settings, &mut rng.0);
+            chameleon_ball.next_change_at = now(&time) + CHAMELEON_CHANGE_INTERVAL;
+            material.color = ball.ball_type.color(&color_blind_mode);
+        } else if time_until_change <= CHAMELEON_WARNING_DURATION {
+            let flashes_left = time_until_change.as_millis() / CHAMELEON_FLASH_INTERVAL.as_millis();
+            if flashes_left.is_multiple_of(2) {
+                material.color = CHAMELEON_FLASH_COLOR;
+            } else {
+                material.color = ball.ball_type.color(&color_blind_mode);
+            }
+        }
     }
 }
 
@@ -1931,9 +4728,10 @@ fn handle_extra_points_effect(
 fn unfreeze_entities(
     mut commands: Commands,
     frozen_query: Query<(Entity, &Frozen), With<RigidBody>>,
+    time: Res<Time>,
 ) {
     for (entity, frozen) in frozen_query.iter() {
-        if Instant::now().saturating_duration_since(frozen.unfreeze_at) > Duration::ZERO {
+        if now(&time).saturating_duration_since(frozen.unfreeze_at) > Duration::ZERO {
             unfreeze_entity(entity, &mut commands);
             commands.entity(entity).insert(frozen.original_velocity);
         }
@@ -1957,9 +4755,10 @@ fn unfreeze_entity(entity: Entity, commands: &mut Commands) {
 fn unresize_entities(
     mut commands: Commands,
     mut resized_query: Query<(Entity, &Resized, &mut Mesh2dHandle, &mut Collider)>,
+    time: Res<Time>,
 ) {
     for (entity, resized, mut mesh, mut collider) in resized_query.iter_mut() {
-        if Instant::now().saturating_duration_since(resized.unresize_at) > Duration::ZERO {
+        if now(&time).saturating_duration_since(resized.unresize_at) > Duration::ZERO {
             *mesh = resized.original_mesh.clone();
             *collider = resized.original_collider.clone();
             commands.entity(entity).remove::<Resized>();
@@ -1967,6 +4766,48 @@ fn unresize_entities(
     }
 }
 
+/// Moves score areas that move over the course of the level
+fn move_score_areas(mut query: Query<(&MovingScoreArea, &mut Transform)>, time: Res<Time>) {
+    for (moving_score_area, mut transform) in query.iter_mut() {
+        let elapsed = now(&time).saturating_duration_since(moving_score_area.started_at);
+        *transform = moving_score_area
+            .movement
+            .transform_at(&moving_score_area.start_transform, elapsed);
+    }
+}
+
+/// Pushes balls around with force fields
+fn apply_force_fields(
+    force_fields_query: Query<(&ForceFieldKind, &Transform)>,
+    mut balls_query: Query<(&Transform, &ReadMassProperties, &mut ExternalForce), With<Ball>>,
+) {
+    for (ball_transform, mass_properties, mut external_force) in balls_query.iter_mut() {
+        let acceleration: Vec2 = force_fields_query
+            .iter()
+            .map(|(kind, field_transform)| {
+                kind.acceleration_at(
+                    (ball_transform.translation - field_transform.translation).truncate(),
+                )
+            })
+            .sum();
+
+        // force fields should move heavy balls just as much as light ones, like gravity does
+        let force = acceleration * mass_properties.0.mass;
+        if external_force.force != force {
+            external_force.force = force;
+        }
+    }
+}
+
+/// Spins vortexes so it's clear which way they swirl balls
+fn spin_vortexes(time: Res<Time>, mut query: Query<(&ForceFieldKind, &mut Transform)>) {
+    for (kind, mut transform) in query.iter_mut() {
+        if let ForceFieldKind::Vortex { strength, .. } = kind {
+            transform.rotate_z(strength * VORTEX_SPIN_SPEED * time.delta_seconds());
+        }
+    }
+}
+
 /// Handles animating hit score areas
 fn animate_score_area_hit(
     mut commands: Commands,
@@ -1978,6 +4819,7 @@ fn animate_score_area_hit(
     )>,
     color_blind_mode: Res<ColorBlindMode>,
     mut materials: ResMut<Assets<ColorMaterial>>,
+    time: Res<Time>,
 ) {
     for (entity, score_area, animation, material_handle) in query.iter() {
         let material = materials
@@ -1991,7 +4833,7 @@ fn animate_score_area_hit(
             1.0
         };
 
-        let animation_progress: f32 = Instant::now()
+        let animation_progress: f32 = now(&time)
             .saturating_duration_since(animation.hit_time)
             .as_secs_f32()
             / SCORE_AREA_HIT_ANIMATION_DURATION.as_secs_f32();
@@ -2019,13 +4861,28 @@ fn update_score_display(
     }
 }
 
+/// Keeps the score displays for each player up to date in versus mode
+fn update_versus_score_display(
+    versus_scores: Res<VersusScores>,
+    mut score_text_query: Query<(&mut Text, &VersusScoreText)>,
+) {
+    for (mut text, score_text) in score_text_query.iter_mut() {
+        text.sections[0].value = format!(
+            "{}: {}",
+            score_text.0.name(),
+            versus_scores.get(score_text.0)
+        );
+    }
+}
+
 /// Keeps the remaining time display up to date
 fn update_time_display(
     end_time: Res<LevelEndTime>,
     mut time_text_query: Query<&mut Text, With<TimeText>>,
+    time: Res<Time>,
 ) {
     for mut text in time_text_query.iter_mut() {
-        let time_left = end_time.0.saturating_duration_since(Instant::now());
+        let time_left = end_time.0.saturating_duration_since(now(&time));
         let seconds_left = time_left.as_secs();
         if seconds_left <= 5 {
             text.sections[0].value = format!("{:.1}", time_left.as_millis() as f32 / 1000.0);
@@ -2055,6 +4912,56 @@ fn update_time_display(
     }
 }
 
+/// Keeps the time survived display up to date in endless mode
+fn update_survival_time_display(
+    start_time: Res<LevelStartTime>,
+    mut time_text_query: Query<&mut Text, With<TimeText>>,
+    time: Res<Time>,
+) {
+    for mut text in time_text_query.iter_mut() {
+        text.sections[0].value = format_time(now(&time).saturating_duration_since(start_time.0));
+    }
+}
+
+/// Keeps the time display counting up in time attack mode, and compares it to the best time for the level
+fn update_time_attack_display(
+    start_time: Res<LevelStartTime>,
+    level_settings: Res<LevelSettings>,
+    bests: Res<TimeAttackBests>,
+    mut time_text_query: Query<&mut Text, With<TimeText>>,
+    mut split_text_query: Query<&mut Text, (With<SplitText>, Without<TimeText>)>,
+    time: Res<Time>,
+) {
+    let elapsed = now(&time).saturating_duration_since(start_time.0);
+    for mut text in time_text_query.iter_mut() {
+        text.sections[0].value = format_precise_time(elapsed);
+    }
+
+    if let Some(best) = bests.0.get(&level_settings.id) {
+        for mut text in split_text_query.iter_mut() {
+            if elapsed <= *best {
+                text.sections[0].value = format!("-{}", format_precise_time(*best - elapsed));
+                text.sections[0].style.color = SPLIT_AHEAD_COLOR;
+            } else {
+                text.sections[0].value = format!("+{}", format_precise_time(elapsed - *best));
+                text.sections[0].style.color = SPLIT_BEHIND_COLOR;
+            }
+        }
+    }
+}
+
+/// Keeps the lives display up to date
+fn update_lives_display(
+    lives: Res<Lives>,
+    mut lives_text_query: Query<&mut Text, With<LivesText>>,
+) {
+    if lives.is_changed() {
+        for mut text in lives_text_query.iter_mut() {
+            text.sections[0].value = format!("lives: {}", lives.0);
+        }
+    }
+}
+
 /// Keeps the rotation sensitivity display up to date
 fn update_rotate_sensitivity_display(
     rotate_sensitivity: Res<RotateSensitivity>,
@@ -2068,25 +4975,219 @@ fn update_rotate_sensitivity_display(
 }
 
 /// Ends the level when the timer is up
-fn end_level(mut next_state: ResMut<NextState<GameState>>, end_time: Res<LevelEndTime>) {
-    if Instant::now().saturating_duration_since(end_time.0) > Duration::ZERO {
+fn end_level(
+    mut next_state: ResMut<NextState<GameState>>,
+    end_time: Res<LevelEndTime>,
+    time: Res<Time>,
+) {
+    if now(&time).saturating_duration_since(end_time.0) > Duration::ZERO {
         next_state.set(GameState::BetweenLevels);
     }
 }
 
-/// Starts playing the background music
+/// Goes back to the menu when the demo is over or someone presses something
+fn end_demo(
+    mut next_state: ResMut<NextState<GameState>>,
+    end_time: Res<LevelEndTime>,
+    keycode: Res<Input<KeyCode>>,
+    mouse_buttons: Res<Input<MouseButton>>,
+    time: Res<Time>,
+) {
+    if now(&time) >= end_time.0
+        || keycode.get_just_pressed().next().is_some()
+        || mouse_buttons.get_just_pressed().next().is_some()
+    {
+        next_state.set(GameState::Menu);
+    }
+}
+
+/// Goes back to the level editor when the level being tried out is over or the player presses escape
+fn end_editor_test(
+    mut next_state: ResMut<NextState<GameState>>,
+    end_time: Res<LevelEndTime>,
+    keycode: Res<Input<KeyCode>>,
+    time: Res<Time>,
+) {
+    if now(&time) >= end_time.0 || keycode.just_pressed(KeyCode::Escape) {
+        next_state.set(GameState::LevelEditor);
+    }
+}
+
+/// Ends a versus match when time runs out
+fn end_versus_match(
+    mut next_state: ResMut<NextState<GameState>>,
+    end_time: Res<LevelEndTime>,
+    time: Res<Time>,
+) {
+    if now(&time) >= end_time.0 {
+        next_state.set(GameState::Summary);
+    }
+}
+
+/// Ends a time attack level as soon as the target score is reached, or when time runs out
+#[allow(clippy::too_many_arguments)]
+fn end_time_attack_level(
+    mut commands: Commands,
+    mut next_state: ResMut<NextState<GameState>>,
+    mut bests: ResMut<TimeAttackBests>,
+    score: Res<Score>,
+    level_settings: Res<LevelSettings>,
+    start_time: Res<LevelStartTime>,
+    end_time: Res<LevelEndTime>,
+    time: Res<Time>,
+) {
+    let now = now(&time);
+    let time = if score.0 >= level_settings.min_score {
+        Some(now.saturating_duration_since(start_time.0))
+    } else if now >= end_time.0 {
+        None
+    } else {
+        return;
+    };
+
+    let previous_best = bests.0.get(&level_settings.id).copied();
+    if let Some(time) = time {
+        if previous_best.is_none_or(|best| time < best) {
+            bests.0.insert(level_settings.id, time);
+            storage::save(TIME_ATTACK_BESTS_SAVE_KEY, &*bests);
+        }
+    }
+
+    commands.insert_resource(TimeAttackResult {
+        time,
+        previous_best,
+    });
+    next_state.set(GameState::BetweenLevels);
+}
+
+/// Decides whether this daily challenge attempt is the scored one or just practice
+fn start_daily_challenge_attempt(
+    mut daily_challenge: ResMut<DailyChallenge>,
+    mut scores: ResMut<DailyChallengeScores>,
+) {
+    daily_challenge.practice = scores.0.contains_key(&daily_challenge.day);
+    if !daily_challenge.practice {
+        // record the attempt right away, so it still counts if the game is closed partway through
+        scores.0.insert(daily_challenge.day, 0);
+        storage::save(DAILY_CHALLENGE_SCORES_SAVE_KEY, &*scores);
+    }
+}
+
+/// Ends the daily challenge when the timer is up, recording the score if this was the scored attempt
+fn end_daily_challenge(
+    mut next_state: ResMut<NextState<GameState>>,
+    mut scores: ResMut<DailyChallengeScores>,
+    end_time: Res<LevelEndTime>,
+    score: Res<Score>,
+    daily_challenge: Res<DailyChallenge>,
+    time: Res<Time>,
+) {
+    if now(&time).saturating_duration_since(end_time.0) > Duration::ZERO {
+        if !daily_challenge.practice {
+            scores.0.insert(daily_challenge.day, score.0);
+            storage::save(DAILY_CHALLENGE_SCORES_SAVE_KEY, &*scores);
+        }
+        next_state.set(GameState::Summary);
+    }
+}
+
+/// Ends an endless run when the player runs out of lives
+fn end_endless_run(
+    mut commands: Commands,
+    mut next_state: ResMut<NextState<GameState>>,
+    lives: Res<Lives>,
+    start_time: Res<LevelStartTime>,
+    time: Res<Time>,
+) {
+    if lives.0 == 0 {
+        commands.insert_resource(TimeSurvived(
+            now(&time).saturating_duration_since(start_time.0),
+        ));
+        next_state.set(GameState::Summary);
+    }
+}
+
+/// Makes endless mode harder the longer it goes on
+fn ramp_up_endless_mode(
+    mut commands: Commands,
+    mut meshes: ResMut<Assets<Mesh>>,
+    mut materials: ResMut<Assets<ColorMaterial>>,
+    mut level_settings: ResMut<LevelSettings>,
+    start_time: Res<LevelStartTime>,
+    color_blind_mode: Res<ColorBlindMode>,
+    time: Res<Time>,
+) {
+    let elapsed = now(&time).saturating_duration_since(start_time.0);
+    let steps = (elapsed.as_secs_f32() / ENDLESS_RAMP_INTERVAL.as_secs_f32()) as u32;
+
+    // spawn rate goes up smoothly, everything else goes up in steps
+    let ramp_progress =
+        (elapsed.as_secs_f32() / ENDLESS_TIME_BETWEEN_GROUPS_RAMP_DURATION.as_secs_f32()).min(1.0);
+    let time_between_groups = ENDLESS_MAX_TIME_BETWEEN_GROUPS.as_secs_f32().lerp(
+        &ENDLESS_MIN_TIME_BETWEEN_GROUPS.as_secs_f32(),
+        &ramp_progress,
+    );
+    level_settings.time_between_groups = Duration::from_secs_f32(time_between_groups);
+
+    let balls_per_group =
+        (ENDLESS_STARTING_BALLS_PER_GROUP + steps / 2).min(ENDLESS_MAX_BALLS_PER_GROUP);
+    if level_settings.balls_per_group != balls_per_group {
+        level_settings.balls_per_group = balls_per_group;
+    }
+
+    // add a new score area (and so a new type of ball) every few steps
+    let all_score_areas = ScoreAreaSettings::endless();
+    let score_area_count = (2 + steps as usize / 3).min(all_score_areas.len());
+    for score_area_settings in &all_score_areas[level_settings.score_areas.len()..score_area_count]
+    {
+        spawn_score_area(
+            &mut commands,
+            &mut meshes,
+            &mut materials,
+            score_area_settings,
+            &color_blind_mode,
+            now(&time),
+        )
+        .insert(GameComponent);
+        level_settings.score_areas.push(score_area_settings.clone());
+    }
+
+    // once all the ball types are in play, start mixing in chameleon balls
+    let chameleon_steps = steps.saturating_sub(3 * (all_score_areas.len() as u32 - 2));
+    let chameleon_ball_chance = (f64::from(chameleon_steps)
+        * ENDLESS_CHAMELEON_BALL_CHANCE_INCREASE)
+        .min(ENDLESS_MAX_CHAMELEON_BALL_CHANCE);
+    if level_settings.chameleon_ball_chance != chameleon_ball_chance {
+        level_settings.chameleon_ball_chance = chameleon_ball_chance;
+    }
+}
+
+/// Starts playing the background music, with the tension layer silent
 fn start_backround_music(
     mut commands: Commands,
     audio: Res<Audio>,
+    audio_settings: Res<AudioSettings>,
     audio_assets: Res<AudioAssets>,
     audio_sinks: Res<Assets<AudioSink>>,
 ) {
-    let handle = audio_sinks.get_handle(audio.play_with_settings(
-        audio_assets.game_music.clone(),
-        PlaybackSettings::LOOP.with_volume(BG_MUSIC_VOLUME * MASTER_VOLUME),
+    let main = audio_sinks.get_handle(
+        audio.play_with_settings(
+            audio_assets.game_music.clone(),
+            PlaybackSettings::LOOP
+                .with_volume(audio_settings.music_volume(BG_MUSIC_VOLUME * MUSIC_CALM_LEVEL)),
+        ),
+    );
+    let tension = audio_sinks.get_handle(audio.play_with_settings(
+        audio_assets.menu_music.clone(),
+        PlaybackSettings::LOOP.with_volume(0.0),
     ));
 
-    commands.insert_resource(GameMusicController(handle));
+    commands.insert_resource(GameMusicController {
+        main,
+        tension,
+        main_level: MUSIC_CALM_LEVEL,
+        tension_level: 0.0,
+    });
 }
 
 /// Stops playing the background music
@@ -2094,9 +5195,68 @@ fn stop_background_music(
     music_controller: Res<GameMusicController>,
     audio_sinks: Res<Assets<AudioSink>>,
 ) {
-    if let Some(sink) = audio_sinks.get(&music_controller.0) {
-        sink.stop();
+    for handle in [&music_controller.main, &music_controller.tension] {
+        if let Some(sink) = audio_sinks.get(handle) {
+            sink.stop();
+        }
+    }
+}
+
+/// Fades the layers of the background music in and out to follow how intense the level is
+#[allow(clippy::too_many_arguments)]
+fn update_music_intensity(
+    mut music_controller: ResMut<GameMusicController>,
+    audio_sinks: Res<Assets<AudioSink>>,
+    audio_settings: Res<AudioSettings>,
+    balls_query: Query<(), With<Ball>>,
+    end_time: Res<LevelEndTime>,
+    score: Res<Score>,
+    level_settings: Res<LevelSettings>,
+    game_mode: Res<GameMode>,
+    time: Res<Time>,
+) {
+    let behind = *game_mode != GameMode::Versus && score.0 < level_settings.min_score;
+    let intensity = (balls_query.iter().count() as f32 / MUSIC_FULL_INTENSITY_BALLS
+        + if behind { MUSIC_BEHIND_INTENSITY } else { 0.0 })
+    .min(1.0);
+
+    let tension = if game_mode.counts_down() {
+        let time_left = end_time.0.saturating_duration_since(now(&time));
+        1.0 - (time_left.as_secs_f32() / MUSIC_TENSION_TIME.as_secs_f32()).min(1.0)
+    } else {
+        0.0
+    };
+
+    let target_main_level = (MUSIC_CALM_LEVEL + (1.0 - MUSIC_CALM_LEVEL) * intensity)
+        * (1.0 - MUSIC_TENSION_CROSSFADE * tension);
+    let max_change = MUSIC_FADE_SPEED * time.delta_seconds();
+    let main_level = fade_towards(music_controller.main_level, target_main_level, max_change);
+    let tension_level = fade_towards(music_controller.tension_level, tension, max_change);
+    music_controller.main_level = main_level;
+    music_controller.tension_level = tension_level;
+
+    if let Some(sink) = audio_sinks.get(&music_controller.main) {
+        sink.set_volume(audio_settings.music_volume(BG_MUSIC_VOLUME * main_level));
     }
+
+    if let Some(sink) = audio_sinks.get(&music_controller.tension) {
+        sink.set_volume(audio_settings.music_volume(BG_MUSIC_VOLUME * tension_level));
+    }
+}
+
+/// Moves the provided value towards the target, without changing it by more than `max_change`
+fn fade_towards(value: f32, target: f32, max_change: f32) -> f32 {
+    value + (target - value).clamp(-max_change, max_change)
+}
+
+/// Goes back to fitting the default view size in the window
+fn reset_view_size(mut view_size: ResMut<ViewSize>) {
+    *view_size = ViewSize::default();
+}
+
+/// Turns gravity back off outside of levels
+fn reset_gravity(mut rapier_configuration: ResMut<RapierConfiguration>) {
+    rapier_configuration.gravity = Vec2::ZERO;
 }
 
 /// Despawns entities that need to be despawned
@@ -0,0 +1,835 @@
+use std::time::Duration;
+
+use bevy::{ecs::system::EntityCommands, window::PrimaryWindow};
+
+use crate::*;
+
+const CUSTOM_LEVEL_SAVE_KEY: &str = "custom_level";
+
+const SIDEBAR_WIDTH: f32 = 270.0;
+const EDITOR_FONT_SIZE: f32 = 20.0;
+const EDITOR_TEXT_COLOR: Color = Color::rgb(0.9, 0.9, 0.9);
+const EDITOR_HEADING_COLOR: Color = Color::rgb(0.0, 0.9, 1.0);
+
+/// How far from the edge of the arena things can be placed
+const PLACEMENT_MARGIN: f32 = 20.0;
+/// How close to something a click with the erase tool has to be to remove it
+const ERASE_DISTANCE: f32 = 60.0;
+const BUMPER_RADIUS: f32 = 30.0;
+const PLACED_WALL_THICKNESS: f32 = 20.0;
+/// How much spawned balls can be pushed sideways, relative to the direction their spawn point launches them
+const SPAWN_IMPULSE_WOBBLE: f32 = 10.0;
+
+pub struct LevelEditorPlugin;
+
+impl Plugin for LevelEditorPlugin {
+    fn build(&self, app: &mut App) {
+        app.insert_resource(EditedLevel(
+            storage::load::<LevelSettings>(CUSTOM_LEVEL_SAVE_KEY)
+                .unwrap_or_else(LevelSettings::custom),
+        ))
+        .init_resource::<EditorTools>()
+        .add_system(level_editor_setup.in_schedule(OnEnter(GameState::LevelEditor)))
+        .add_system(
+            despawn_components_system::<LevelEditorComponent>
+                .in_schedule(OnExit(GameState::LevelEditor)),
+        )
+        .add_system(reset_editor_view_size.in_schedule(OnExit(GameState::LevelEditor)))
+        .add_system(place_with_tool.run_if(in_state(GameState::LevelEditor)))
+        .add_system(tool_button_system.run_if(in_state(GameState::LevelEditor)))
+        .add_system(parameter_button_system.run_if(in_state(GameState::LevelEditor)))
+        .add_system(
+            update_parameter_texts
+                .after(parameter_button_system)
+                .after(tool_button_system)
+                .run_if(in_state(GameState::LevelEditor)),
+        )
+        .add_system(
+            refresh_level_preview
+                .after(place_with_tool)
+                .after(parameter_button_system)
+                .after(editor_action_button_system)
+                .run_if(in_state(GameState::LevelEditor)),
+        )
+        .add_system(editor_action_button_system.run_if(in_state(GameState::LevelEditor)));
+    }
+}
+
+/// The level being built in the level editor
+#[derive(Resource)]
+pub struct EditedLevel(pub LevelSettings);
+
+/// What clicking in the arena does in the level editor
+#[derive(Clone, Copy, PartialEq, Eq)]
+enum EditorTool {
+    SpawnPoint,
+    ScoreArea,
+    Wall,
+    Bumper,
+    Erase,
+}
+
+impl EditorTool {
+    const ALL: [EditorTool; 5] = [
+        EditorTool::SpawnPoint,
+        EditorTool::ScoreArea,
+        EditorTool::Wall,
+        EditorTool::Bumper,
+        EditorTool::Erase,
+    ];
+
+    /// Gets the name of this tool
+    fn name(&self) -> &str {
+        match self {
+            EditorTool::SpawnPoint => "spawn point",
+            EditorTool::ScoreArea => "score area",
+            EditorTool::Wall => "wall",
+            EditorTool::Bumper => "bumper",
+            EditorTool::Erase => "erase",
+        }
+    }
+}
+
+/// The selected tool and the settings for the things it places
+#[derive(Resource)]
+struct EditorTools {
+    tool: EditorTool,
+    /// The type of ball that placed score areas score points for
+    ball_type: BallType,
+    /// How far to either side of where it's placed a spawn point can spawn balls
+    spawn_spread: f32,
+    /// The weakest initial impulse placed spawn points give balls
+    min_impulse: f32,
+    /// The strongest initial impulse placed spawn points give balls
+    max_impulse: f32,
+    /// The length of placed walls
+    wall_length: f32,
+    /// The rotation of placed walls, in degrees counterclockwise
+    wall_rotation: f32,
+}
+
+impl Default for EditorTools {
+    fn default() -> Self {
+        EditorTools {
+            tool: EditorTool::SpawnPoint,
+            ball_type: BallType::A,
+            spawn_spread: 60.0,
+            min_impulse: 5.0,
+            max_impulse: 20.0,
+            wall_length: 200.0,
+            wall_rotation: 0.0,
+        }
+    }
+}
+
+/// A value that can be adjusted with buttons in the level editor
+#[derive(Clone, Copy, PartialEq, Eq)]
+enum EditorParameter {
+    TimeBetweenGroups,
+    MaxRespiteTime,
+    BallsPerGroup,
+    Duration,
+    MinScore,
+    BallType,
+    SpawnSpread,
+    MinImpulse,
+    MaxImpulse,
+    WallLength,
+    WallRotation,
+}
+
+impl EditorParameter {
+    /// Parameters of the level itself
+    const LEVEL: [EditorParameter; 5] = [
+        EditorParameter::TimeBetweenGroups,
+        EditorParameter::MaxRespiteTime,
+        EditorParameter::BallsPerGroup,
+        EditorParameter::Duration,
+        EditorParameter::MinScore,
+    ];
+
+    /// Parameters of the things the tools place
+    const TOOLS: [EditorParameter; 6] = [
+        EditorParameter::BallType,
+        EditorParameter::SpawnSpread,
+        EditorParameter::MinImpulse,
+        EditorParameter::MaxImpulse,
+        EditorParameter::WallLength,
+        EditorParameter::WallRotation,
+    ];
+
+    /// Builds the text describing the current value of this parameter
+    fn text(&self, level_settings: &LevelSettings, tools: &EditorTools) -> String {
+        match self {
+            EditorParameter::TimeBetweenGroups => format!(
+                "time between groups: {}s",
+                level_settings.time_between_groups.as_secs()
+            ),
+            EditorParameter::MaxRespiteTime => format!(
+                "max respite time: {}s",
+                level_settings.max_respite_time.as_secs()
+            ),
+            EditorParameter::BallsPerGroup => {
+                format!("balls per group: {}", level_settings.balls_per_group)
+            }
+            EditorParameter::Duration => {
+                format!("duration: {}", format_time(level_settings.duration))
+            }
+            EditorParameter::MinScore => format!("score needed: {}", level_settings.min_score),
+            EditorParameter::BallType => "score area color".to_string(),
+            EditorParameter::SpawnSpread => format!("spawn spread: {}", tools.spawn_spread),
+            EditorParameter::MinImpulse => format!("min impulse: {}", tools.min_impulse),
+            EditorParameter::MaxImpulse => format!("max impulse: {}", tools.max_impulse),
+            EditorParameter::WallLength => format!("wall length: {}", tools.wall_length),
+            EditorParameter::WallRotation => format!("wall rotation: {}", tools.wall_rotation),
+        }
+    }
+
+    /// Changes this parameter by the provided number of steps
+    fn adjust(&self, level_settings: &mut LevelSettings, tools: &mut EditorTools, steps: i32) {
+        let adjust_seconds = |duration: Duration, step: i64, min: i64| {
+            let seconds = (duration.as_secs() as i64 + (step * i64::from(steps))).max(min);
+            Duration::from_secs(seconds as u64)
+        };
+
+        match self {
+            EditorParameter::TimeBetweenGroups => {
+                level_settings.time_between_groups =
+                    adjust_seconds(level_settings.time_between_groups, 1, 1);
+            }
+            EditorParameter::MaxRespiteTime => {
+                level_settings.max_respite_time =
+                    adjust_seconds(level_settings.max_respite_time, 1, 0);
+            }
+            EditorParameter::BallsPerGroup => {
+                level_settings.balls_per_group = level_settings
+                    .balls_per_group
+                    .saturating_add_signed(steps)
+                    .max(1);
+            }
+            EditorParameter::Duration => {
+                level_settings.duration = adjust_seconds(level_settings.duration, 4, 4);
+            }
+            EditorParameter::MinScore => {
+                level_settings.min_score = (level_settings.min_score + steps).max(0);
+            }
+            EditorParameter::BallType => {
+                tools.ball_type = tools.ball_type.cycled(steps);
+                tools.tool = EditorTool::ScoreArea;
+            }
+            EditorParameter::SpawnSpread => {
+                tools.spawn_spread = (tools.spawn_spread + (10.0 * steps as f32)).max(0.0);
+                tools.tool = EditorTool::SpawnPoint;
+            }
+            EditorParameter::MinImpulse => {
+                tools.min_impulse =
+                    (tools.min_impulse + steps as f32).clamp(0.0, tools.max_impulse);
+                tools.tool = EditorTool::SpawnPoint;
+            }
+            EditorParameter::MaxImpulse => {
+                tools.max_impulse = (tools.max_impulse + steps as f32).max(tools.min_impulse);
+                tools.tool = EditorTool::SpawnPoint;
+            }
+            EditorParameter::WallLength => {
+                tools.wall_length = (tools.wall_length + (20.0 * steps as f32)).max(20.0);
+                tools.tool = EditorTool::Wall;
+            }
+            EditorParameter::WallRotation => {
+                tools.wall_rotation =
+                    (tools.wall_rotation + (15.0 * steps as f32)).rem_euclid(180.0);
+                tools.tool = EditorTool::Wall;
+            }
+        }
+    }
+}
+
+#[derive(Component)]
+struct LevelEditorComponent;
+
+/// Marks entities that are part of the view of the level being edited
+#[derive(Component)]
+struct LevelPreview;
+
+#[derive(Component)]
+struct ToolButton(EditorTool);
+
+#[derive(Component)]
+struct SelectedToolText;
+
+#[derive(Component)]
+struct ParameterButton {
+    parameter: EditorParameter,
+    steps: i32,
+}
+
+#[derive(Component)]
+struct ParameterText(EditorParameter);
+
+#[derive(Component)]
+struct EditorStatusText;
+
+#[derive(Component, Clone, Copy)]
+enum EditorActionButton {
+    TestPlay,
+    Save,
+    Clear,
+    NewLevel,
+    BackToMenu,
+}
+
+/// Sets up the level editor
+fn level_editor_setup(
+    mut commands: Commands,
+    asset_server: Res<AssetServer>,
+    mut edited_level: ResMut<EditedLevel>,
+    tools: Res<EditorTools>,
+) {
+    // make sure the view of the level gets built
+    edited_level.set_changed();
+
+    commands.insert_resource(ViewSize(edited_level.0.arena.view_size()));
+
+    // level settings sidebar
+    commands
+        .spawn(sidebar_node(UiRect {
+            left: Val::Px(0.0),
+            top: Val::Px(0.0),
+            ..default()
+        }))
+        .insert(LevelEditorComponent)
+        .with_children(|parent| {
+            spawn_editor_heading(parent, &asset_server, "level editor");
+
+            for parameter in EditorParameter::LEVEL {
+                spawn_parameter_row(parent, &asset_server, parameter, &edited_level.0, &tools);
+            }
+
+            parent
+                .spawn(NodeBundle {
+                    style: Style {
+                        flex_direction: FlexDirection::Row,
+                        flex_wrap: FlexWrap::Wrap,
+                        max_size: Size::width(Val::Px(SIDEBAR_WIDTH)),
+                        margin: UiRect::top(Val::Px(15.0)),
+                        ..default()
+                    },
+                    ..default()
+                })
+                .with_children(|parent| {
+                    for (action, text) in [
+                        (EditorActionButton::TestPlay, "test play"),
+                        (EditorActionButton::Save, "save"),
+                        (EditorActionButton::Clear, "clear"),
+                        (EditorActionButton::NewLevel, "new level"),
+                        (EditorActionButton::BackToMenu, "back to menu"),
+                    ] {
+                        spawn_editor_button(parent, &asset_server, text).insert(action);
+                    }
+                });
+
+            parent
+                .spawn(
+                    TextBundle::from_section(
+                        "",
+                        TextStyle {
+                            font: asset_server.load(MAIN_FONT),
+                            font_size: EDITOR_FONT_SIZE,
+                            color: EDITOR_TEXT_COLOR,
+                        },
+                    )
+                    .with_style(Style {
+                        margin: UiRect::top(Val::Px(10.0)),
+                        max_size: Size::width(Val::Px(SIDEBAR_WIDTH)),
+                        ..default()
+                    }),
+                )
+                .insert(EditorStatusText);
+        });
+
+    // tools sidebar
+    commands
+        .spawn(sidebar_node(UiRect {
+            right: Val::Px(0.0),
+            top: Val::Px(0.0),
+            ..default()
+        }))
+        .insert(LevelEditorComponent)
+        .with_children(|parent| {
+            spawn_editor_heading(parent, &asset_server, "tools");
+
+            parent
+                .spawn(NodeBundle {
+                    style: Style {
+                        flex_direction: FlexDirection::Row,
+                        flex_wrap: FlexWrap::Wrap,
+                        max_size: Size::width(Val::Px(SIDEBAR_WIDTH)),
+                        ..default()
+                    },
+                    ..default()
+                })
+                .with_children(|parent| {
+                    for tool in EditorTool::ALL {
+                        spawn_editor_button(parent, &asset_server, tool.name())
+                            .insert(ToolButton(tool));
+                    }
+                });
+
+            parent
+                .spawn(TextBundle::from_section(
+                    format!("placing: {}", tools.tool.name()),
+                    TextStyle {
+                        font: asset_server.load(MONO_FONT),
+                        font_size: EDITOR_FONT_SIZE,
+                        color: Color::WHITE,
+                    },
+                ))
+                .insert(SelectedToolText);
+
+            for parameter in EditorParameter::TOOLS {
+                spawn_parameter_row(parent, &asset_server, parameter, &edited_level.0, &tools);
+            }
+
+            parent.spawn(
+                TextBundle::from_section(
+                    "click in the arena to place things with the selected tool",
+                    TextStyle {
+                        font: asset_server.load(MAIN_FONT),
+                        font_size: EDITOR_FONT_SIZE,
+                        color: EDITOR_TEXT_COLOR,
+                    },
+                )
+                .with_style(Style {
+                    margin: UiRect::top(Val::Px(10.0)),
+                    max_size: Size::width(Val::Px(SIDEBAR_WIDTH)),
+                    ..default()
+                }),
+            );
+        });
+}
+
+/// Builds a column along one side of the screen, positioned with the provided offsets
+fn sidebar_node(position: UiRect) -> NodeBundle {
+    NodeBundle {
+        style: Style {
+            size: Size::new(Val::Px(SIDEBAR_WIDTH), Val::Percent(100.0)),
+            position_type: PositionType::Absolute,
+            position,
+            padding: UiRect::all(Val::Px(5.0)),
+            flex_direction: FlexDirection::Column,
+            justify_content: JustifyContent::Start,
+            align_items: AlignItems::FlexStart,
+            ..default()
+        },
+        ..default()
+    }
+}
+
+/// Spawns a heading in one of the level editor's sidebars
+fn spawn_editor_heading(parent: &mut ChildBuilder, asset_server: &AssetServer, text: &str) {
+    parent.spawn(
+        TextBundle::from_section(
+            text,
+            TextStyle {
+                font: asset_server.load(MONO_FONT),
+                font_size: 28.0,
+                color: EDITOR_HEADING_COLOR,
+            },
+        )
+        .with_style(Style {
+            margin: UiRect::vertical(Val::Px(5.0)),
+            ..default()
+        }),
+    );
+}
+
+/// Spawns a small button with the provided text
+fn spawn_editor_button<'w, 's, 'a>(
+    parent: &'a mut ChildBuilder<'w, 's, '_>,
+    asset_server: &AssetServer,
+    text: &str,
+) -> EntityCommands<'w, 's, 'a> {
+    let mut button = parent.spawn(ButtonBundle {
+        style: Style {
+            margin: UiRect::all(Val::Px(3.0)),
+            padding: UiRect {
+                left: Val::Px(8.0),
+                right: Val::Px(8.0),
+                top: Val::Px(3.0),
+                bottom: Val::Px(3.0),
+            },
+            justify_content: JustifyContent::Center,
+            align_items: AlignItems::Center,
+            ..default()
+        },
+        background_color: NORMAL_BUTTON.into(),
+        ..default()
+    });
+
+    button.with_children(|parent| {
+        parent.spawn(TextBundle::from_section(
+            text,
+            TextStyle {
+                font: asset_server.load(MAIN_FONT),
+                font_size: EDITOR_FONT_SIZE,
+                color: NORMAL_BUTTON_TEXT_COLOR,
+            },
+        ));
+    });
+
+    button
+}
+
+/// Spawns a row with the value of the provided parameter and buttons to change it
+fn spawn_parameter_row(
+    parent: &mut ChildBuilder,
+    asset_server: &AssetServer,
+    parameter: EditorParameter,
+    level_settings: &LevelSettings,
+    tools: &EditorTools,
+) {
+    parent
+        .spawn(NodeBundle {
+            style: Style {
+                flex_direction: FlexDirection::Row,
+                align_items: AlignItems::Center,
+                ..default()
+            },
+            ..default()
+        })
+        .with_children(|parent| {
+            spawn_editor_button(parent, asset_server, "-").insert(ParameterButton {
+                parameter,
+                steps: -1,
+            });
+            spawn_editor_button(parent, asset_server, "+").insert(ParameterButton {
+                parameter,
+                steps: 1,
+            });
+            parent
+                .spawn(TextBundle::from_section(
+                    parameter.text(level_settings, tools),
+                    TextStyle {
+                        font: asset_server.load(MAIN_FONT),
+                        font_size: EDITOR_FONT_SIZE,
+                        color: EDITOR_TEXT_COLOR,
+                    },
+                ))
+                .insert(ParameterText(parameter));
+        });
+}
+
+/// Puts the view size back to normal when leaving the level editor
+fn reset_editor_view_size(mut view_size: ResMut<ViewSize>) {
+    *view_size = ViewSize::default();
+}
+
+/// Rebuilds the view of the level whenever it changes, and shows anything that needs fixing before it can be played
+fn refresh_level_preview(
+    mut commands: Commands,
+    mut meshes: ResMut<Assets<Mesh>>,
+    mut materials: ResMut<Assets<ColorMaterial>>,
+    edited_level: Res<EditedLevel>,
+    color_blind_mode: Res<ColorBlindMode>,
+    preview_query: Query<Entity, With<LevelPreview>>,
+    mut status_text_query: Query<&mut Text, With<EditorStatusText>>,
+) {
+    if !edited_level.is_changed() {
+        return;
+    }
+
+    for entity in preview_query.iter() {
+        commands.entity(entity).despawn_recursive();
+    }
+
+    for entity in spawn_level_preview(
+        &mut commands,
+        &mut meshes,
+        &mut materials,
+        &edited_level.0,
+        &color_blind_mode,
+    ) {
+        commands
+            .entity(entity)
+            .insert(LevelPreview)
+            .insert(LevelEditorComponent);
+    }
+
+    for mut text in status_text_query.iter_mut() {
+        text.sections[0].value = level_problem(&edited_level.0)
+            .unwrap_or_default()
+            .to_string();
+    }
+}
+
+/// Finds something about the level that would stop it from being playable, if there is anything
+fn level_problem(level_settings: &LevelSettings) -> Option<&'static str> {
+    if level_settings.spawn_points.is_empty() {
+        Some("add a spawn point so balls have somewhere to come from")
+    } else if level_settings.score_areas.is_empty() {
+        Some("add a score area so balls have somewhere to go")
+    } else {
+        None
+    }
+}
+
+/// Uses the selected tool where the arena is clicked
+fn place_with_tool(
+    mut edited_level: ResMut<EditedLevel>,
+    tools: Res<EditorTools>,
+    mouse_buttons: Res<Input<MouseButton>>,
+    window_query: Query<&Window, With<PrimaryWindow>>,
+    camera_query: Query<(&Camera, &GlobalTransform), With<MainCamera>>,
+    interaction_query: Query<&Interaction>,
+) {
+    if !mouse_buttons.just_pressed(MouseButton::Left) {
+        return;
+    }
+
+    // clicks on buttons aren't meant for the arena
+    if interaction_query
+        .iter()
+        .any(|interaction| *interaction != Interaction::None)
+    {
+        return;
+    }
+
+    let Ok(window) = window_query.get_single() else {
+        return;
+    };
+    let Ok((camera, camera_transform)) = camera_query.get_single() else {
+        return;
+    };
+    let Some(position) = window
+        .cursor_position()
+        .and_then(|cursor| camera.viewport_to_world_2d(camera_transform, cursor))
+    else {
+        return;
+    };
+
+    let level_settings = &mut edited_level.0;
+    if position
+        .abs()
+        .cmpgt(level_settings.arena.size() / 2.0)
+        .any()
+    {
+        // outside the arena
+        return;
+    }
+    let position = level_settings
+        .arena
+        .clamp_inside(position, PLACEMENT_MARGIN);
+
+    match tools.tool {
+        EditorTool::SpawnPoint => {
+            level_settings
+                .spawn_points
+                .push(spawn_point_at(position, &tools));
+        }
+        EditorTool::ScoreArea => {
+            level_settings
+                .score_areas
+                .push(ScoreAreaSettings::at(tools.ball_type, position));
+        }
+        EditorTool::Wall => {
+            level_settings.obstacles.push(ObstacleSettings::wall(
+                position,
+                tools.wall_length,
+                PLACED_WALL_THICKNESS,
+                tools.wall_rotation,
+            ));
+        }
+        EditorTool::Bumper => {
+            level_settings
+                .obstacles
+                .push(ObstacleSettings::bumper(position, BUMPER_RADIUS));
+        }
+        EditorTool::Erase => erase_nearest(level_settings, position),
+    }
+}
+
+/// Builds a spawn point at the provided position that launches balls towards the middle of the arena
+fn spawn_point_at(position: Vec2, tools: &EditorTools) -> SpawnPoint {
+    let towards_middle = (-position).normalize_or_zero();
+    let impulse_range = |direction: f32| {
+        if direction > 0.0 {
+            tools.min_impulse..=tools.max_impulse
+        } else {
+            -tools.max_impulse..=-tools.min_impulse
+        }
+    };
+
+    // balls spread out sideways from the direction they're launched in
+    if towards_middle.x.abs() > towards_middle.y.abs() {
+        SpawnPoint {
+            start_position_range_x: position.x..=position.x,
+            start_position_range_y: (position.y - tools.spawn_spread)
+                ..=(position.y + tools.spawn_spread),
+            start_impulse_range_x: impulse_range(towards_middle.x),
+            start_impulse_range_y: -SPAWN_IMPULSE_WOBBLE..=SPAWN_IMPULSE_WOBBLE,
+        }
+    } else {
+        SpawnPoint {
+            start_position_range_x: (position.x - tools.spawn_spread)
+                ..=(position.x + tools.spawn_spread),
+            start_position_range_y: position.y..=position.y,
+            start_impulse_range_x: -SPAWN_IMPULSE_WOBBLE..=SPAWN_IMPULSE_WOBBLE,
+            start_impulse_range_y: impulse_range(towards_middle.y),
+        }
+    }
+}
+
+/// Removes the spawn point, score area, or obstacle closest to the provided position, if any are close enough
+fn erase_nearest(level_settings: &mut LevelSettings, position: Vec2) {
+    let spawn_points = level_settings
+        .spawn_points
+        .iter()
+        .map(|spawn_point| spawn_point.center());
+    let score_areas = level_settings
+        .score_areas
+        .iter()
+        .map(|score_area| score_area.position);
+    let obstacles = level_settings
+        .obstacles
+        .iter()
+        .map(|obstacle| obstacle.position);
+
+    let nearest = spawn_points
+        .enumerate()
+        .map(|(i, center)| (0, i, center))
+        .chain(score_areas.enumerate().map(|(i, center)| (1, i, center)))
+        .chain(obstacles.enumerate().map(|(i, center)| (2, i, center)))
+        .map(|(list, i, center)| (list, i, center.distance(position)))
+        .filter(|(_, _, distance)| *distance <= ERASE_DISTANCE)
+        .min_by(|(_, _, a), (_, _, b)| a.total_cmp(b));
+
+    match nearest {
+        Some((0, i, _)) => {
+            level_settings.spawn_points.remove(i);
+        }
+        Some((1, i, _)) => {
+            level_settings.score_areas.remove(i);
+        }
+        Some((_, i, _)) => {
+            level_settings.obstacles.remove(i);
+        }
+        None => (),
+    }
+}
+
+type InteractedToolButtonTuple = (Changed<Interaction>, With<ToolButton>);
+
+/// Handles interactions with the tool buttons.
+fn tool_button_system(
+    mut tools: ResMut<EditorTools>,
+    interaction_query: Query<(&Interaction, &ToolButton), InteractedToolButtonTuple>,
+) {
+    for (interaction, tool_button) in interaction_query.iter() {
+        if *interaction == Interaction::Clicked {
+            tools.tool = tool_button.0;
+        }
+    }
+}
+
+type InteractedParameterButtonTuple = (Changed<Interaction>, With<ParameterButton>);
+
+/// Handles interactions with the buttons for changing parameters.
+fn parameter_button_system(
+    mut edited_level: ResMut<EditedLevel>,
+    mut tools: ResMut<EditorTools>,
+    interaction_query: Query<(&Interaction, &ParameterButton), InteractedParameterButtonTuple>,
+) {
+    for (interaction, parameter_button) in interaction_query.iter() {
+        if *interaction == Interaction::Clicked {
+            parameter_button.parameter.adjust(
+                &mut edited_level.0,
+                &mut tools,
+                parameter_button.steps,
+            );
+        }
+    }
+}
+
+/// Keeps the parameter and selected tool texts up to date
+fn update_parameter_texts(
+    edited_level: Res<EditedLevel>,
+    tools: Res<EditorTools>,
+    color_blind_mode: Res<ColorBlindMode>,
+    mut parameter_text_query: Query<(&mut Text, &ParameterText), Without<SelectedToolText>>,
+    mut selected_tool_text_query: Query<&mut Text, With<SelectedToolText>>,
+) {
+    if !edited_level.is_changed() && !tools.is_changed() {
+        return;
+    }
+
+    for (mut text, parameter_text) in parameter_text_query.iter_mut() {
+        text.sections[0].value = parameter_text.0.text(&edited_level.0, &tools);
+        if parameter_text.0 == EditorParameter::BallType {
+            // shown in the color of the balls placed score areas are for
+            text.sections[0].style.color = tools.ball_type.color(&color_blind_mode);
+        }
+    }
+
+    for mut text in selected_tool_text_query.iter_mut() {
+        text.sections[0].value = format!("placing: {}", tools.tool.name());
+    }
+}
+
+type InteractedEditorActionButtonTuple = (Changed<Interaction>, With<EditorActionButton>);
+
+/// Handles interactions with the test play, save, clear, new level, and back to menu buttons.
+#[allow(clippy::too_many_arguments)]
+fn editor_action_button_system(
+    mut next_state: ResMut<NextState<GameState>>,
+    mut edited_level: ResMut<EditedLevel>,
+    mut game_mode: ResMut<GameMode>,
+    mut level_settings: ResMut<LevelSettings>,
+    mut two_players: ResMut<TwoPlayers>,
+    interaction_query: Query<
+        (&Interaction, &EditorActionButton),
+        InteractedEditorActionButtonTuple,
+    >,
+    mut status_text_query: Query<&mut Text, With<EditorStatusText>>,
+) {
+    for (interaction, action) in interaction_query.iter() {
+        if *interaction != Interaction::Clicked {
+            continue;
+        }
+
+        let status = match action {
+            EditorActionButton::TestPlay => {
+                if let Some(problem) = level_problem(&edited_level.0) {
+                    problem
+                } else {
+                    *game_mode = GameMode::EditorTest;
+                    *level_settings = edited_level.0.clone();
+                    two_players.0 = false;
+                    next_state.set(GameState::GameLoading);
+                    ""
+                }
+            }
+            EditorActionButton::Save => {
+                storage::save(CUSTOM_LEVEL_SAVE_KEY, &edited_level.0);
+                "saved"
+            }
+            EditorActionButton::Clear => {
+                let level = &mut edited_level.0;
+                level.spawn_points.clear();
+                level.score_areas.clear();
+                level.obstacles.clear();
+                continue;
+            }
+            EditorActionButton::NewLevel => {
+                edited_level.0 = LevelSettings::custom();
+                continue;
+            }
+            EditorActionButton::BackToMenu => {
+                next_state.set(GameState::Menu);
+                continue;
+            }
+        };
+
+        for mut text in status_text_query.iter_mut() {
+            text.sections[0].value = status.to_string();
+        }
+    }
+}
@@ -0,0 +1,256 @@
+use serde::{Deserialize, Serialize};
+
+use crate::*;
+
+const CAMPAIGN_PROGRESS_SAVE_KEY: &str = "campaign_progress";
+
+pub struct LevelSelectPlugin;
+
+impl Plugin for LevelSelectPlugin {
+    fn build(&self, app: &mut App) {
+        app.insert_resource(
+            storage::load::<CampaignProgress>(CAMPAIGN_PROGRESS_SAVE_KEY).unwrap_or_default(),
+        )
+        .add_system(
+            record_campaign_progress
+                .in_schedule(OnEnter(GameState::BetweenLevels))
+                .run_if(resource_equals(GameMode::Campaign)),
+        )
+        .add_system(level_select_setup.in_schedule(OnEnter(GameState::LevelSelect)))
+        .add_system(
+            despawn_components_system::<LevelSelectComponent>
+                .in_schedule(OnExit(GameState::LevelSelect)),
+        )
+        .add_system(level_button_system.run_if(in_state(GameState::LevelSelect)))
+        .add_system(back_to_menu_button_system.run_if(in_state(GameState::LevelSelect)));
+    }
+}
+
+/// How far the player has gotten in the campaign
+#[derive(Resource, Serialize, Deserialize)]
+pub struct CampaignProgress {
+    /// The ID of the furthest level the player has reached
+    pub furthest_level: usize,
+}
+
+impl Default for CampaignProgress {
+    fn default() -> Self {
+        CampaignProgress { furthest_level: 1 }
+    }
+}
+
+#[derive(Component)]
+struct LevelSelectComponent;
+
+#[derive(Component)]
+struct LevelButton(usize);
+
+#[derive(Component)]
+struct BackToMenuButton;
+
+/// Keeps track of the furthest level reached in the solo campaign
+fn record_campaign_progress(
+    mut progress: ResMut<CampaignProgress>,
+    score: Res<Score>,
+    level_settings: Res<LevelSettings>,
+    two_players: Res<TwoPlayers>,
+) {
+    if !two_players.0
+        && score.0 >= level_settings.min_score
+        && level_settings.id + 1 > progress.furthest_level
+    {
+        progress.furthest_level = level_settings.id + 1;
+        storage::save(CAMPAIGN_PROGRESS_SAVE_KEY, &*progress);
+    }
+}
+
+/// Sets up the level select screen
+fn level_select_setup(
+    mut commands: Commands,
+    asset_server: Res<AssetServer>,
+    progress: Res<CampaignProgress>,
+    high_scores: Res<HighScores>,
+) {
+    commands
+        .spawn(NodeBundle {
+            style: Style {
+                size: Size::new(Val::Percent(100.0), Val::Percent(100.0)),
+                flex_direction: FlexDirection::Column,
+                justify_content: JustifyContent::Center,
+                align_items: AlignItems::Center,
+                ..default()
+            },
+            ..default()
+        })
+        .insert(LevelSelectComponent)
+        .with_children(|parent| {
+            parent.spawn(
+                TextBundle::from_section(
+                    "level select",
+                    TextStyle {
+                        font: asset_server.load(TITLE_FONT),
+                        font_size: 75.0,
+                        color: Color::rgb(0.0, 0.9, 1.0),
+                    },
+                )
+                .with_text_alignment(TextAlignment::Center)
+                .with_style(Style {
+                    margin: UiRect {
+                        bottom: Val::Px(10.0),
+                        ..default()
+                    },
+                    ..default()
+                }),
+            );
+
+            // total stars
+            let total_stars = high_scores.total_stars();
+            let max_stars = progress.furthest_level * MAX_STARS_PER_LEVEL;
+            parent.spawn(
+                TextBundle::from_section(
+                    format!("{total_stars} of {max_stars} stars earned"),
+                    TextStyle {
+                        font: asset_server.load(MONO_FONT),
+                        font_size: 30.0,
+                        color: Color::WHITE,
+                    },
+                )
+                .with_text_alignment(TextAlignment::Center)
+                .with_style(Style {
+                    margin: UiRect {
+                        bottom: Val::Px(20.0),
+                        ..default()
+                    },
+                    ..default()
+                }),
+            );
+
+            // level buttons
+            parent
+                .spawn(NodeBundle {
+                    style: Style {
+                        size: Size::new(Val::Percent(80.0), Val::Auto),
+                        flex_direction: FlexDirection::Row,
+                        flex_wrap: FlexWrap::Wrap,
+                        justify_content: JustifyContent::Center,
+                        align_items: AlignItems::Center,
+                        margin: UiRect {
+                            bottom: Val::Px(20.0),
+                            ..default()
+                        },
+                        ..default()
+                    },
+                    ..default()
+                })
+                .with_children(|parent| {
+                    let mut level_settings = LevelSettings::first_level();
+                    while level_settings.id <= progress.furthest_level {
+                        let best_score = high_scores.best(level_settings.id);
+                        let best_score_text = match best_score {
+                            Some(best_score) if best_score >= level_settings.min_score => {
+                                format!("best: {best_score}")
+                            }
+                            _ => "not completed".to_string(),
+                        };
+
+                        let stars = best_score
+                            .map(|best_score| level_settings.stars(best_score))
+                            .unwrap_or(0);
+
+                        spawn_level_button(
+                            parent,
+                            &asset_server,
+                            level_settings.id,
+                            &best_score_text,
+                            stars,
+                        );
+
+                        level_settings = level_settings.next_level();
+                    }
+                });
+
+            spawn_menu_button(parent, &asset_server, "back to menu").insert(BackToMenuButton);
+        });
+}
+
+/// Spawns a button for starting the level with the provided ID
+fn spawn_level_button(
+    parent: &mut ChildBuilder,
+    asset_server: &AssetServer,
+    level_id: usize,
+    best_score_text: &str,
+    stars: usize,
+) {
+    parent
+        .spawn(ButtonBundle {
+            style: Style {
+                size: Size::new(Val::Px(170.0), Val::Auto),
+                margin: UiRect::all(Val::Px(5.0)),
+                padding: UiRect::all(Val::Px(10.0)),
+                flex_direction: FlexDirection::Column,
+                justify_content: JustifyContent::Center,
+                align_items: AlignItems::Center,
+                ..default()
+            },
+            background_color: NORMAL_BUTTON.into(),
+            ..default()
+        })
+        .insert(LevelButton(level_id))
+        .with_children(|parent| {
+            parent.spawn(TextBundle::from_section(
+                format!("level {level_id}"),
+                TextStyle {
+                    font: asset_server.load(MONO_FONT),
+                    font_size: 30.0,
+                    color: NORMAL_BUTTON_TEXT_COLOR,
+                },
+            ));
+            parent.spawn(TextBundle::from_section(
+                best_score_text,
+                TextStyle {
+                    font: asset_server.load(MAIN_FONT),
+                    font_size: 21.0,
+                    color: NORMAL_BUTTON_TEXT_COLOR,
+                },
+            ));
+            parent.spawn(TextBundle::from_sections(star_text_sections(
+                stars,
+                asset_server,
+                30.0,
+            )));
+        });
+}
+
+type InteractedLevelButtonTuple = (Changed<Interaction>, With<LevelButton>);
+
+/// Handles interactions with the level buttons.
+fn level_button_system(
+    mut next_state: ResMut<NextState<GameState>>,
+    mut game_mode: ResMut<GameMode>,
+    mut level_settings: ResMut<LevelSettings>,
+    mut two_players: ResMut<TwoPlayers>,
+    interaction_query: Query<(&Interaction, &LevelButton), InteractedLevelButtonTuple>,
+) {
+    for (interaction, level_button) in interaction_query.iter() {
+        if *interaction == Interaction::Clicked {
+            *game_mode = GameMode::Campaign;
+            two_players.0 = false;
+            *level_settings = LevelSettings::for_level(level_button.0);
+            next_state.set(GameState::GameLoading);
+        }
+    }
+}
+
+type InteractedBackToMenuButtonTuple = (Changed<Interaction>, With<BackToMenuButton>);
+
+/// Handles interactions with the back to menu button.
+fn back_to_menu_button_system(
+    mut next_state: ResMut<NextState<GameState>>,
+    interaction_query: Query<&Interaction, InteractedBackToMenuButtonTuple>,
+) {
+    for interaction in interaction_query.iter() {
+        if *interaction == Interaction::Clicked {
+            next_state.set(GameState::Menu);
+        }
+    }
+}
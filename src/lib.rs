@@ -0,0 +1,258 @@
+use std::time::Duration;
+
+use bevy::{
+    diagnostic::{FrameTimeDiagnosticsPlugin, LogDiagnosticsPlugin},
+    input::common_conditions::input_toggle_active,
+    prelude::*,
+    window::{PrimaryWindow, WindowResized, WindowResolution},
+};
+use bevy_inspector_egui::quick::WorldInspectorPlugin;
+use bevy_rapier2d::prelude::*;
+
+mod menu;
+use bevy_wasm_window_resize::WindowResizePlugin;
+use menu::*;
+
+mod game;
+use game::*;
+
+mod between_levels;
+use between_levels::*;
+
+mod summary;
+use summary::*;
+
+mod level_select;
+use level_select::*;
+
+mod level_editor;
+use level_editor::*;
+
+mod audio_settings;
+use audio_settings::*;
+
+mod sounds;
+use sounds::*;
+
+pub mod simulation;
+
+mod storage;
+
+const DEV_MODE: bool = false;
+
+const TITLE_FONT: &str = "fonts/TurretRoad-Bold.ttf";
+const MAIN_FONT: &str = "fonts/SofiaSans-Light.ttf";
+const MONO_FONT: &str = "fonts/MajorMonoDisplay-Regular.ttf";
+
+const PIXELS_PER_METER: f32 = 100.0;
+
+const SECONDS_PER_DAY: u64 = 60 * 60 * 24;
+
+const WINDOW_WIDTH: f32 = 1280.0;
+const WINDOW_HEIGHT: f32 = 720.0;
+
+const NORMAL_BUTTON_TEXT_COLOR: Color = Color::rgb(0.9, 0.9, 0.9);
+const DISABLED_BUTTON_TEXT_COLOR: Color = Color::rgb(0.5, 0.5, 0.5);
+
+const NORMAL_BUTTON: Color = Color::rgb(0.25, 0.25, 0.25);
+const DISABLED_BUTTON: Color = Color::rgb(0.1, 0.1, 0.1);
+const HOVERED_BUTTON: Color = Color::rgb(0.35, 0.35, 0.35);
+const PRESSED_BUTTON: Color = Color::rgb(0.35, 0.75, 0.35);
+
+#[derive(Debug, Clone, Copy, Default, Eq, PartialEq, Hash, States)]
+pub enum GameState {
+    #[default]
+    Menu,
+    GameLoading,
+    Game,
+    BetweenLevels,
+    Summary,
+    LevelSelect,
+    LevelEditor,
+}
+
+#[derive(Component)]
+pub struct MainCamera;
+
+#[derive(Component)]
+pub struct DisabledButton;
+
+/// The size of the area of the world that should fit in the window
+#[derive(Resource)]
+pub struct ViewSize(pub Vec2);
+
+impl Default for ViewSize {
+    fn default() -> Self {
+        ViewSize(Vec2::new(WINDOW_WIDTH, WINDOW_HEIGHT))
+    }
+}
+
+/// Runs the game
+pub fn run() {
+    let mut app = App::new();
+    app.insert_resource(ClearColor(Color::BLACK))
+        .insert_resource(Msaa::Sample4)
+        .add_plugins(DefaultPlugins.set(WindowPlugin {
+            primary_window: Some(Window {
+                title: "Extreme Bounce Party 2000".into(),
+                resolution: WindowResolution::new(WINDOW_WIDTH, WINDOW_HEIGHT),
+                // Tells wasm to resize the window according to the available canvas
+                fit_canvas_to_parent: true,
+                // Tells wasm not to override default event handling, like F5, Ctrl+R etc.
+                prevent_default_event_handling: false,
+                ..default()
+            }),
+            ..default()
+        }))
+        .add_plugin(RapierPhysicsPlugin::<GamePhysicsHooks>::pixels_per_meter(
+            PIXELS_PER_METER,
+        ))
+        .insert_resource(RapierConfiguration {
+            gravity: Vec2::ZERO,
+            ..default()
+        })
+        .add_plugin(WindowResizePlugin)
+        .add_state::<GameState>()
+        .init_resource::<ViewSize>()
+        .add_startup_system(setup)
+        .add_plugin(MenuPlugin)
+        .add_plugin(GamePlugin)
+        .add_plugin(BetweenLevelsPlugin)
+        .add_plugin(SummaryPlugin)
+        .add_plugin(LevelSelectPlugin)
+        .add_plugin(LevelEditorPlugin)
+        .add_plugin(AudioSettingsPlugin)
+        .add_plugin(SoundsPlugin)
+        .add_system(zoom_based_on_window_size)
+        .add_system(button_color_system);
+
+    if DEV_MODE {
+        app.add_system(bevy::window::close_on_esc)
+            .add_system(start_test_level.run_if(in_state(GameState::Menu)))
+            .add_plugin(LogDiagnosticsPlugin::default())
+            .add_plugin(
+                WorldInspectorPlugin::new().run_if(input_toggle_active(false, KeyCode::Equals)),
+            )
+            .add_plugin(RapierDebugRenderPlugin::default());
+        #[allow(clippy::default_constructed_unit_structs)]
+        app.add_plugin(FrameTimeDiagnosticsPlugin::default());
+    }
+
+    app.run();
+}
+
+fn setup(mut commands: Commands) {
+    /* bloom would be nice
+    commands
+        .spawn((
+            Camera2dBundle {
+                camera: Camera {
+                    hdr: true, // 1. HDR is required for bloom
+                    ..default()
+                },
+                tonemapping: Tonemapping::TonyMcMapface, // 2. Using a tonemapper that desaturates to white is recommended
+                ..default()
+            },
+            BloomSettings {
+                intensity: 0.2,
+                ..default()
+            }, // 3. Enable bloom for the camera
+        ))
+        .insert(MainCamera);
+    */
+    // but bloom performance is real bad on some machines
+    commands.spawn(Camera2dBundle::default()).insert(MainCamera);
+}
+
+/// Adjusts the camera zoom when the window is resized or the size of the area to show changes
+fn zoom_based_on_window_size(
+    mut camera_query: Query<&mut OrthographicProjection, With<MainCamera>>,
+    window_query: Query<&Window, With<PrimaryWindow>>,
+    view_size: Res<ViewSize>,
+    mut resize_reader: EventReader<WindowResized>,
+) {
+    let mut projection = camera_query.single_mut();
+
+    let window_size = if let Some(event) = resize_reader.iter().last() {
+        Vec2::new(event.width, event.height)
+    } else if view_size.is_changed() {
+        let Ok(window) = window_query.get_single() else {
+            return;
+        };
+        Vec2::new(window.width(), window.height())
+    } else {
+        return;
+    };
+
+    projection.scale = (view_size.0.x / window_size.x).max(view_size.0.y / window_size.y);
+}
+
+type InteractedButtonTuple = (Changed<Interaction>, With<Button>, Without<DisabledButton>);
+
+/// Handles changing button colors when they're interacted with.
+fn button_color_system(
+    mut interaction_query: Query<(&Interaction, &mut BackgroundColor), InteractedButtonTuple>,
+) {
+    for (interaction, mut color) in interaction_query.iter_mut() {
+        *color = match *interaction {
+            Interaction::Clicked => PRESSED_BUTTON.into(),
+            Interaction::Hovered => HOVERED_BUTTON.into(),
+            Interaction::None => NORMAL_BUTTON.into(),
+        }
+    }
+}
+
+/// Generic system that takes a component as a parameter, and will despawn all entities with that component
+fn despawn_components_system<T: Component>(
+    to_despawn: Query<Entity, With<T>>,
+    mut commands: Commands,
+) {
+    despawn_components(to_despawn, &mut commands);
+}
+
+fn despawn_components<T: Component>(to_despawn: Query<Entity, With<T>>, commands: &mut Commands) {
+    for entity in to_despawn.iter() {
+        commands.entity(entity).despawn_recursive();
+    }
+}
+
+/// Formats the provided duration as minutes and seconds
+fn format_time(duration: Duration) -> String {
+    let seconds = duration.as_secs();
+    format!("{}:{:02}", seconds / 60, seconds % 60)
+}
+
+/// Formats the provided duration as minutes, seconds, and tenths of a second
+fn format_precise_time(duration: Duration) -> String {
+    let tenths = duration.as_millis() / 100;
+    format!("{}:{:02}.{}", tenths / 600, (tenths / 10) % 60, tenths % 10)
+}
+
+/// Gets the number of days since the Unix epoch (in UTC)
+fn current_day() -> u64 {
+    let since_epoch = instant::SystemTime::now()
+        .duration_since(instant::SystemTime::UNIX_EPOCH)
+        .unwrap_or_default();
+    since_epoch.as_secs() / SECONDS_PER_DAY
+}
+
+/// Formats the day with the provided number of days since the Unix epoch like 2023-04-01
+fn format_date(day: u64) -> String {
+    // from http://howardhinnant.github.io/date_algorithms.html#civil_from_days
+    let days = day as i64 + 719_468;
+    let era = days.div_euclid(146_097);
+    let day_of_era = days.rem_euclid(146_097);
+    let year_of_era =
+        (day_of_era - day_of_era / 1460 + day_of_era / 36524 - day_of_era / 146_096) / 365;
+    let day_of_year = day_of_era - (365 * year_of_era + year_of_era / 4 - year_of_era / 100);
+    let shifted_month = (5 * day_of_year + 2) / 153;
+    let day_of_month = day_of_year - (153 * shifted_month + 2) / 5 + 1;
+    let month = if shifted_month < 10 {
+        shifted_month + 3
+    } else {
+        shifted_month - 9
+    };
+    let year = year_of_era + era * 400 + i64::from(month <= 2);
+
+    format!("{year}-{month:02}-{day_of_month:02}")
+}
@@ -1,16 +1,36 @@
+use bevy::{ecs::system::EntityCommands, input::mouse::MouseMotion};
+use instant::Instant;
+use rand::Rng;
+
 use crate::*;
 
 const INTRO_TEXT: &str = include_str!("intro_text.txt");
 
+/// How long the menu has to be left alone before the demo starts
+const DEMO_IDLE_TIME: Duration = Duration::from_secs(30);
+/// The highest campaign level the demo might show
+const DEMO_MAX_LEVEL: usize = 8;
+
 pub struct MenuPlugin;
 
 impl Plugin for MenuPlugin {
     fn build(&self, app: &mut App) {
         app.add_system(menu_setup.in_schedule(OnEnter(GameState::Menu)))
+            .add_system(reset_players.in_schedule(OnEnter(GameState::Menu)))
+            .add_system(start_demo_when_idle.run_if(in_state(GameState::Menu)))
             .add_system(
                 despawn_components_system::<MenuComponent>.in_schedule(OnExit(GameState::Menu)),
             )
-            .add_system(start_button_system);
+            .add_system(start_button_system)
+            .add_system(coop_button_system)
+            .add_system(coop_with_bot_button_system)
+            .add_system(versus_button_system)
+            .add_system(endless_button_system)
+            .add_system(time_attack_button_system)
+            .add_system(daily_challenge_button_system)
+            .add_system(level_select_button_system)
+            .add_system(level_editor_button_system)
+            .add_system(audio_settings_button_system);
     }
 }
 
@@ -20,6 +40,75 @@ struct MenuComponent;
 #[derive(Component)]
 struct StartButton;
 
+#[derive(Component)]
+struct CoopButton;
+
+#[derive(Component)]
+struct CoopWithBotButton;
+
+#[derive(Component)]
+struct VersusButton;
+
+#[derive(Component)]
+struct EndlessButton;
+
+#[derive(Component)]
+struct TimeAttackButton;
+
+#[derive(Component)]
+struct DailyChallengeButton;
+
+#[derive(Component)]
+struct LevelSelectButton;
+
+#[derive(Component)]
+struct LevelEditorButton;
+
+#[derive(Component)]
+struct AudioSettingsButton;
+
+/// When something was last pressed or moved on the menu
+#[derive(Resource)]
+struct LastMenuInputTime(Instant);
+
+/// Makes sure nobody is left controlled by the bot after going back to the menu
+fn reset_players(mut commands: Commands, mut bot_players: ResMut<BotPlayers>) {
+    bot_players.0.clear();
+    commands.insert_resource(LastMenuInputTime(Instant::now()));
+}
+
+/// Starts the demo if nobody has touched anything on the menu for a while
+#[allow(clippy::too_many_arguments)]
+fn start_demo_when_idle(
+    mut next_state: ResMut<NextState<GameState>>,
+    mut game_mode: ResMut<GameMode>,
+    mut level_settings: ResMut<LevelSettings>,
+    mut two_players: ResMut<TwoPlayers>,
+    mut bot_players: ResMut<BotPlayers>,
+    mut last_input_time: ResMut<LastMenuInputTime>,
+    keycode: Res<Input<KeyCode>>,
+    mouse_buttons: Res<Input<MouseButton>>,
+    mut mouse_motion_events: EventReader<MouseMotion>,
+) {
+    let mouse_moved = mouse_motion_events.iter().count() > 0;
+    if mouse_moved
+        || keycode.get_pressed().next().is_some()
+        || mouse_buttons.get_pressed().next().is_some()
+    {
+        last_input_time.0 = Instant::now();
+        return;
+    }
+
+    if last_input_time.0.elapsed() >= DEMO_IDLE_TIME {
+        *game_mode = GameMode::Demo;
+        *level_settings =
+            LevelSettings::for_level(rand::thread_rng().gen_range(1..=DEMO_MAX_LEVEL));
+        two_players.0 = false;
+        bot_players.0 = vec![Player::One];
+        next_state.set(GameState::GameLoading);
+    }
+}
+
 fn menu_setup(mut commands: Commands, asset_server: Res<AssetServer>) {
     // title text
     commands
@@ -95,11 +184,11 @@ fn menu_setup(mut commands: Commands, asset_server: Res<AssetServer>) {
             );
         });
 
-    // start button
+    // start buttons
     commands
         .spawn(NodeBundle {
             style: Style {
-                // center button
+                // center buttons
                 size: Size::new(Val::Percent(100.0), Val::Auto),
                 position_type: PositionType::Absolute,
                 position: UiRect {
@@ -108,48 +197,243 @@ fn menu_setup(mut commands: Commands, asset_server: Res<AssetServer>) {
                 },
                 justify_content: JustifyContent::Center,
                 align_items: AlignItems::Center,
+                flex_wrap: FlexWrap::Wrap,
                 ..default()
             },
             ..default()
         })
         .insert(MenuComponent)
         .with_children(|parent| {
-            parent
-                .spawn(ButtonBundle {
-                    style: Style {
-                        size: Size::new(Val::Auto, Val::Auto),
-                        justify_content: JustifyContent::Center,
-                        align_items: AlignItems::Center,
-                        padding: UiRect::all(Val::Px(10.0)),
-                        ..default()
-                    },
-                    background_color: NORMAL_BUTTON.into(),
-                    ..default()
-                })
-                .insert(StartButton)
-                .with_children(|parent| {
-                    parent.spawn(TextBundle::from_section(
-                        "let's bounce",
-                        TextStyle {
-                            font: asset_server.load(MONO_FONT),
-                            font_size: 40.0,
-                            color: NORMAL_BUTTON_TEXT_COLOR,
-                        },
-                    ));
-                });
+            spawn_menu_button(parent, &asset_server, "let's bounce").insert(StartButton);
+            spawn_menu_button(parent, &asset_server, "co-op").insert(CoopButton);
+            spawn_menu_button(parent, &asset_server, "co-op with bot").insert(CoopWithBotButton);
+            spawn_menu_button(parent, &asset_server, "versus").insert(VersusButton);
+            spawn_menu_button(parent, &asset_server, "endless").insert(EndlessButton);
+            spawn_menu_button(parent, &asset_server, "time attack").insert(TimeAttackButton);
+            spawn_menu_button(parent, &asset_server, "daily challenge")
+                .insert(DailyChallengeButton);
+            spawn_menu_button(parent, &asset_server, "level select").insert(LevelSelectButton);
+            spawn_menu_button(parent, &asset_server, "level editor").insert(LevelEditorButton);
+            spawn_menu_button(parent, &asset_server, "audio").insert(AudioSettingsButton);
         });
 }
 
+/// Spawns a big menu button with the provided text
+pub fn spawn_menu_button<'w, 's, 'a>(
+    parent: &'a mut ChildBuilder<'w, 's, '_>,
+    asset_server: &AssetServer,
+    text: &str,
+) -> EntityCommands<'w, 's, 'a> {
+    let mut button = parent.spawn(ButtonBundle {
+        style: Style {
+            size: Size::new(Val::Auto, Val::Auto),
+            margin: UiRect::all(Val::Px(10.0)),
+            justify_content: JustifyContent::Center,
+            align_items: AlignItems::Center,
+            padding: UiRect::all(Val::Px(10.0)),
+            ..default()
+        },
+        background_color: NORMAL_BUTTON.into(),
+        ..default()
+    });
+
+    button.with_children(|parent| {
+        parent.spawn(TextBundle::from_section(
+            text,
+            TextStyle {
+                font: asset_server.load(MONO_FONT),
+                font_size: 40.0,
+                color: NORMAL_BUTTON_TEXT_COLOR,
+            },
+        ));
+    });
+
+    button
+}
+
 type InteractedStartButtonTuple = (Changed<Interaction>, With<StartButton>);
 
 /// Handles interactions with the start button.
 fn start_button_system(
     mut next_state: ResMut<NextState<GameState>>,
+    mut game_mode: ResMut<GameMode>,
+    mut level_settings: ResMut<LevelSettings>,
+    mut two_players: ResMut<TwoPlayers>,
     interaction_query: Query<&Interaction, InteractedStartButtonTuple>,
 ) {
     for interaction in interaction_query.iter() {
         if *interaction == Interaction::Clicked {
+            *game_mode = GameMode::Campaign;
+            *level_settings = LevelSettings::first_level();
+            two_players.0 = false;
             next_state.set(GameState::GameLoading);
         }
     }
 }
+
+type InteractedCoopButtonTuple = (Changed<Interaction>, With<CoopButton>);
+
+/// Handles interactions with the co-op button.
+fn coop_button_system(
+    mut next_state: ResMut<NextState<GameState>>,
+    mut game_mode: ResMut<GameMode>,
+    mut level_settings: ResMut<LevelSettings>,
+    mut two_players: ResMut<TwoPlayers>,
+    interaction_query: Query<&Interaction, InteractedCoopButtonTuple>,
+) {
+    for interaction in interaction_query.iter() {
+        if *interaction == Interaction::Clicked {
+            *game_mode = GameMode::Campaign;
+            *level_settings = LevelSettings::first_level();
+            two_players.0 = true;
+            next_state.set(GameState::GameLoading);
+        }
+    }
+}
+
+type InteractedCoopWithBotButtonTuple = (Changed<Interaction>, With<CoopWithBotButton>);
+
+/// Handles interactions with the co-op with bot button.
+fn coop_with_bot_button_system(
+    mut next_state: ResMut<NextState<GameState>>,
+    mut game_mode: ResMut<GameMode>,
+    mut level_settings: ResMut<LevelSettings>,
+    mut two_players: ResMut<TwoPlayers>,
+    mut bot_players: ResMut<BotPlayers>,
+    interaction_query: Query<&Interaction, InteractedCoopWithBotButtonTuple>,
+) {
+    for interaction in interaction_query.iter() {
+        if *interaction == Interaction::Clicked {
+            *game_mode = GameMode::Campaign;
+            *level_settings = LevelSettings::first_level();
+            two_players.0 = true;
+            bot_players.0 = vec![Player::Two];
+            next_state.set(GameState::GameLoading);
+        }
+    }
+}
+
+type InteractedVersusButtonTuple = (Changed<Interaction>, With<VersusButton>);
+
+/// Handles interactions with the versus button.
+fn versus_button_system(
+    mut next_state: ResMut<NextState<GameState>>,
+    mut game_mode: ResMut<GameMode>,
+    mut level_settings: ResMut<LevelSettings>,
+    mut two_players: ResMut<TwoPlayers>,
+    interaction_query: Query<&Interaction, InteractedVersusButtonTuple>,
+) {
+    for interaction in interaction_query.iter() {
+        if *interaction == Interaction::Clicked {
+            *game_mode = GameMode::Versus;
+            *level_settings = LevelSettings::versus();
+            two_players.0 = true;
+            next_state.set(GameState::GameLoading);
+        }
+    }
+}
+
+type InteractedEndlessButtonTuple = (Changed<Interaction>, With<EndlessButton>);
+
+/// Handles interactions with the endless mode button.
+fn endless_button_system(
+    mut next_state: ResMut<NextState<GameState>>,
+    mut game_mode: ResMut<GameMode>,
+    mut level_settings: ResMut<LevelSettings>,
+    mut two_players: ResMut<TwoPlayers>,
+    interaction_query: Query<&Interaction, InteractedEndlessButtonTuple>,
+) {
+    for interaction in interaction_query.iter() {
+        if *interaction == Interaction::Clicked {
+            *game_mode = GameMode::Endless;
+            *level_settings = LevelSettings::endless();
+            two_players.0 = false;
+            next_state.set(GameState::GameLoading);
+        }
+    }
+}
+
+type InteractedTimeAttackButtonTuple = (Changed<Interaction>, With<TimeAttackButton>);
+
+/// Handles interactions with the time attack button.
+fn time_attack_button_system(
+    mut next_state: ResMut<NextState<GameState>>,
+    mut game_mode: ResMut<GameMode>,
+    mut level_settings: ResMut<LevelSettings>,
+    mut two_players: ResMut<TwoPlayers>,
+    interaction_query: Query<&Interaction, InteractedTimeAttackButtonTuple>,
+) {
+    for interaction in interaction_query.iter() {
+        if *interaction == Interaction::Clicked {
+            *game_mode = GameMode::TimeAttack;
+            *level_settings = LevelSettings::first_level();
+            two_players.0 = false;
+            next_state.set(GameState::GameLoading);
+        }
+    }
+}
+
+type InteractedDailyChallengeButtonTuple = (Changed<Interaction>, With<DailyChallengeButton>);
+
+/// Handles interactions with the daily challenge button.
+fn daily_challenge_button_system(
+    mut commands: Commands,
+    mut next_state: ResMut<NextState<GameState>>,
+    mut game_mode: ResMut<GameMode>,
+    mut level_settings: ResMut<LevelSettings>,
+    mut two_players: ResMut<TwoPlayers>,
+    interaction_query: Query<&Interaction, InteractedDailyChallengeButtonTuple>,
+) {
+    for interaction in interaction_query.iter() {
+        if *interaction == Interaction::Clicked {
+            let daily_challenge = DailyChallenge::today();
+            *game_mode = GameMode::DailyChallenge;
+            *level_settings = LevelSettings::daily_challenge(daily_challenge.day);
+            two_players.0 = false;
+            commands.insert_resource(daily_challenge);
+            next_state.set(GameState::GameLoading);
+        }
+    }
+}
+
+type InteractedLevelSelectButtonTuple = (Changed<Interaction>, With<LevelSelectButton>);
+
+/// Handles interactions with the level select button.
+fn level_select_button_system(
+    mut next_state: ResMut<NextState<GameState>>,
+    interaction_query: Query<&Interaction, InteractedLevelSelectButtonTuple>,
+) {
+    for interaction in interaction_query.iter() {
+        if *interaction == Interaction::Clicked {
+            next_state.set(GameState::LevelSelect);
+        }
+    }
+}
+
+type InteractedLevelEditorButtonTuple = (Changed<Interaction>, With<LevelEditorButton>);
+
+/// Handles interactions with the level editor button.
+fn level_editor_button_system(
+    mut next_state: ResMut<NextState<GameState>>,
+    interaction_query: Query<&Interaction, InteractedLevelEditorButtonTuple>,
+) {
+    for interaction in interaction_query.iter() {
+        if *interaction == Interaction::Clicked {
+            next_state.set(GameState::LevelEditor);
+        }
+    }
+}
+
+type InteractedAudioSettingsButtonTuple = (Changed<Interaction>, With<AudioSettingsButton>);
+
+/// Handles interactions with the audio settings button.
+fn audio_settings_button_system(
+    mut audio_settings_open: ResMut<AudioSettingsOpen>,
+    interaction_query: Query<&Interaction, InteractedAudioSettingsButtonTuple>,
+) {
+    for interaction in interaction_query.iter() {
+        if *interaction == Interaction::Clicked {
+            audio_settings_open.0 = true;
+        }
+    }
+}
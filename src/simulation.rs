@@ -0,0 +1,364 @@
+//! Plays levels headlessly and faster than real time, to help with balancing them without playtesting every change
+
+use std::{
+    collections::HashMap,
+    io::{self, Write},
+    time::Duration,
+};
+
+use bevy::{
+    render::{settings::WgpuSettings, RenderPlugin},
+    time::TimeUpdateStrategy,
+    window::ExitCondition,
+    winit::WinitPlugin,
+};
+use bevy_rapier2d::prelude::*;
+use instant::Instant;
+use rand::{prelude::*, rngs::StdRng};
+
+use crate::*;
+
+/// How long each simulated frame lasts
+const SIMULATED_FRAME_TIME: Duration = Duration::from_nanos(1_000_000_000 / 60);
+
+/// How long a single run can go on for before it's assumed to be stuck, like when an asset fails to load
+const MAX_RUN_TIME: Duration = Duration::from_secs(10 * 60);
+
+/// The seed used to pick the loadouts to simulate, so the same options always simulate the same loadouts
+const LOADOUT_SEED: u64 = 0;
+
+/// How the player shape is controlled during a simulation
+#[derive(Clone, Copy, PartialEq, Eq, Debug)]
+pub enum Controller {
+    /// The built-in bot plays the level
+    Bot,
+    /// Nobody touches the controls
+    Idle,
+    /// The player shape spins clockwise the whole time
+    Spin,
+}
+
+impl Controller {
+    /// Gets the controller with the provided name, if there is one
+    pub fn from_name(name: &str) -> Option<Controller> {
+        match name {
+            "bot" => Some(Controller::Bot),
+            "idle" => Some(Controller::Idle),
+            "spin" => Some(Controller::Spin),
+            _ => None,
+        }
+    }
+}
+
+/// What to simulate
+pub struct SimulationOptions {
+    /// The IDs of the campaign levels to simulate
+    pub levels: Vec<usize>,
+    /// The number of different seeds to play each level and loadout with
+    pub runs: u64,
+    /// The number of different loadouts to try on levels that don't have fixed sides
+    pub loadouts: usize,
+    /// How the player shape is controlled
+    pub controller: Controller,
+}
+
+/// A single play through of a level
+struct Run {
+    level_id: usize,
+    loadout: usize,
+    seed: u64,
+}
+
+/// What happened during a single play through of a level
+#[derive(PartialEq, Debug)]
+struct RunResult {
+    score: i32,
+    side_effect_counts: HashMap<SideType, u32>,
+    /// Whether the run was stopped for going on too long, in which case the score is from when it was stopped
+    timed_out: bool,
+}
+
+/// Simulates the levels described by the provided options and writes a CSV report of the results to `output`
+pub fn run(options: &SimulationOptions, output: &mut impl Write) -> io::Result<()> {
+    let loadouts = random_loadouts(options.loadouts);
+    let mut level_loadouts = HashMap::new();
+    let mut runs = Vec::new();
+    for level_id in &options.levels {
+        let level_settings = LevelSettings::for_level(*level_id);
+        // levels with fixed sides play the same no matter which loadout is configured
+        let mut sides_used: Vec<ConfiguredSides> = Vec::new();
+        for loadout in &loadouts {
+            let sides = level_settings.player_sides(loadout);
+            if !sides_used
+                .iter()
+                .any(|used| loadout_sides(used) == loadout_sides(sides))
+            {
+                sides_used.push(sides.clone());
+            }
+        }
+
+        for loadout in 0..sides_used.len() {
+            for seed in 0..options.runs {
+                runs.push(Run {
+                    level_id: *level_id,
+                    loadout,
+                    seed,
+                });
+            }
+        }
+        level_loadouts.insert(*level_id, sides_used);
+    }
+
+    let results = simulate(options.controller, &runs, &level_loadouts);
+
+    write_report(options, &level_loadouts, &results, output)
+}
+
+/// Plays through each of the provided runs in order
+fn simulate<'a>(
+    controller: Controller,
+    runs: &'a [Run],
+    level_loadouts: &HashMap<usize, Vec<ConfiguredSides>>,
+) -> Vec<(&'a Run, RunResult)> {
+    let mut app = build_app(controller);
+    let mut results = Vec::new();
+    let mut now = Instant::now();
+    let mut remaining_runs = runs.iter();
+    let mut current_run = remaining_runs.next();
+    if let Some(run) = current_run {
+        start_run(&mut app, run, level_loadouts, GameState::GameLoading);
+    }
+
+    let max_run_frames = MAX_RUN_TIME.as_nanos() / SIMULATED_FRAME_TIME.as_nanos();
+    let mut run_frames = 0;
+    while let Some(run) = current_run {
+        now += SIMULATED_FRAME_TIME;
+        app.insert_resource(TimeUpdateStrategy::ManualInstant(now));
+        if controller == Controller::Spin {
+            app.world
+                .resource_mut::<Input<KeyCode>>()
+                .press(ROTATE_CLOCKWISE_KEY);
+        }
+        app.update();
+        run_frames += 1;
+
+        let state = app.world.resource::<State<GameState>>().0;
+        let timed_out = run_frames >= max_run_frames;
+        if state == GameState::BetweenLevels || timed_out {
+            results.push((
+                run,
+                RunResult {
+                    score: app.world.get_resource::<Score>().map_or(0, |score| score.0),
+                    side_effect_counts: app
+                        .world
+                        .get_resource::<SideEffectCounts>()
+                        .map(|counts| counts.0.clone())
+                        .unwrap_or_default(),
+                    timed_out: state != GameState::BetweenLevels,
+                },
+            ));
+            current_run = remaining_runs.next();
+            run_frames = 0;
+            if let Some(run) = current_run {
+                // a run that got stuck loading needs to start loading again, since the game won't work without its assets
+                let next_state = if state == GameState::GameLoading {
+                    GameState::GameLoading
+                } else {
+                    GameState::Game
+                };
+                start_run(&mut app, run, level_loadouts, next_state);
+            }
+        }
+    }
+
+    results
+}
+
+/// Builds an app that plays the game without a window or any rendering
+fn build_app(controller: Controller) -> App {
+    let mut app = App::new();
+    app.add_plugins(
+        DefaultPlugins
+            .set(WindowPlugin {
+                primary_window: None,
+                exit_condition: ExitCondition::DontExit,
+                close_when_requested: false,
+            })
+            .set(RenderPlugin {
+                wgpu_settings: WgpuSettings {
+                    backends: None,
+                    ..default()
+                },
+            })
+            .disable::<WinitPlugin>(),
+    )
+    .add_plugin(RapierPhysicsPlugin::<GamePhysicsHooks>::pixels_per_meter(
+        PIXELS_PER_METER,
+    ))
+    .insert_resource(RapierConfiguration {
+        gravity: Vec2::ZERO,
+        ..default()
+    })
+    .add_state::<GameState>()
+    .init_resource::<ViewSize>()
+    // simulations run much faster than real time, so nothing should be heard
+    .insert_resource(AudioSettings {
+        muted: true,
+        ..default()
+    })
+    .add_plugin(GamePlugin)
+    .add_plugin(SoundsPlugin)
+    .insert_resource(GameMode::Campaign);
+
+    if controller == Controller::Bot {
+        app.insert_resource(BotPlayers(vec![Player::One]));
+    }
+
+    app.setup();
+
+    app
+}
+
+/// Sets up the level and loadout for the provided run, and then moves to the provided state to start playing it
+fn start_run(
+    app: &mut App,
+    run: &Run,
+    level_loadouts: &HashMap<usize, Vec<ConfiguredSides>>,
+    state: GameState,
+) {
+    app.insert_resource(LevelSettings::for_level(run.level_id).with_seed(run.seed));
+    app.insert_resource(level_loadouts[&run.level_id][run.loadout].clone());
+    app.world.resource_mut::<NextState<GameState>>().set(state);
+}
+
+/// Picks the provided number of random loadouts, without repeating side types that can only appear once
+fn random_loadouts(count: usize) -> Vec<ConfiguredSides> {
+    let mut rng = StdRng::seed_from_u64(LOADOUT_SEED);
+    (0..count)
+        .map(|_| {
+            let mut side_types: Vec<SideType> = Vec::new();
+            while side_types.len() < PLAYER_SHAPE_SIDES {
+                let side_type = *SideType::ALL
+                    .choose(&mut rng)
+                    .expect("there should be side types to choose from");
+                if side_type.multiple_allowed() || !side_types.contains(&side_type) {
+                    side_types.push(side_type);
+                }
+            }
+
+            ConfiguredSides(
+                side_types
+                    .into_iter()
+                    .enumerate()
+                    .map(|(i, side_type)| (SideId(i), side_type))
+                    .collect(),
+            )
+        })
+        .collect()
+}
+
+/// Gets the types of the provided sides in order
+fn loadout_sides(configured_sides: &ConfiguredSides) -> Vec<SideType> {
+    (0..PLAYER_SHAPE_SIDES)
+        .map(|i| configured_sides.get(&SideId(i)))
+        .collect()
+}
+
+/// Writes one line per level and loadout with score statistics and the average number of times each type of side was hit per run
+fn write_report(
+    options: &SimulationOptions,
+    level_loadouts: &HashMap<usize, Vec<ConfiguredSides>>,
+    results: &[(&Run, RunResult)],
+    output: &mut impl Write,
+) -> io::Result<()> {
+    write!(
+        output,
+        "level,loadout,runs,timed_out,min_score,mean_score,lowest_score,highest_score,pass_rate"
+    )?;
+    for side_type in SideType::ALL {
+        write!(output, ",{} hits", side_type.name())?;
+    }
+    writeln!(output)?;
+
+    for level_id in &options.levels {
+        let min_score = LevelSettings::for_level(*level_id).min_score;
+        for (loadout, configured_sides) in level_loadouts[level_id].iter().enumerate() {
+            let loadout_results = results
+                .iter()
+                .filter(|(run, _)| run.level_id == *level_id && run.loadout == loadout)
+                .map(|(_, result)| result)
+                .collect::<Vec<_>>();
+            if loadout_results.is_empty() {
+                continue;
+            }
+
+            let runs = loadout_results.len() as f32;
+            let scores = loadout_results
+                .iter()
+                .map(|result| result.score)
+                .collect::<Vec<_>>();
+            let passes = scores.iter().filter(|score| **score >= min_score).count();
+            let timed_out = loadout_results
+                .iter()
+                .filter(|result| result.timed_out)
+                .count();
+            let loadout_name = loadout_sides(configured_sides)
+                .iter()
+                .map(|side_type| side_type.name())
+                .collect::<Vec<_>>()
+                .join(" / ");
+
+            write!(
+                output,
+                "{},{},{},{},{},{:.2},{},{},{:.2}",
+                level_id,
+                loadout_name,
+                loadout_results.len(),
+                timed_out,
+                min_score,
+                scores.iter().sum::<i32>() as f32 / runs,
+                scores.iter().min().unwrap_or(&0),
+                scores.iter().max().unwrap_or(&0),
+                passes as f32 / runs,
+            )?;
+            for side_type in SideType::ALL {
+                let hits = loadout_results
+                    .iter()
+                    .map(|result| result.side_effect_counts.get(&side_type).unwrap_or(&0))
+                    .sum::<u32>();
+                write!(output, ",{:.2}", hits as f32 / runs)?;
+            }
+            writeln!(output)?;
+        }
+    }
+
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn replaying_a_run_gives_the_same_result() {
+        let level_loadouts = HashMap::from([(1, random_loadouts(1))]);
+        let runs = [
+            Run {
+                level_id: 1,
+                loadout: 0,
+                seed: 0,
+            },
+            Run {
+                level_id: 1,
+                loadout: 0,
+                seed: 0,
+            },
+        ];
+
+        let results = simulate(Controller::Idle, &runs, &level_loadouts);
+
+        assert_eq!(results.len(), 2);
+        assert!(!results[0].1.timed_out);
+        assert_eq!(results[0].1, results[1].1);
+    }
+}
@@ -0,0 +1,262 @@
+use std::{collections::VecDeque, f32::consts::PI, slice, time::Duration};
+
+use rand::prelude::*;
+
+use crate::*;
+
+/// The most sound effects that can play at once, so lots of things happening at the same time don't clip
+const MAX_VOICES: usize = 8;
+/// How long a sound effect is assumed to keep playing after it starts, when counting how many are playing at once
+const VOICE_DURATION: Duration = Duration::from_millis(400);
+/// How much faster or slower than normal a sound effect can randomly be played, to make repeated sounds less monotonous
+const PITCH_VARIATION: f32 = 0.08;
+
+const HITS_VOLUME: f32 = 0.4;
+const SCORES_VOLUME: f32 = 0.4;
+const EFFECTS_VOLUME: f32 = 0.5;
+
+/// How much faster to play the hit sound when balls hit each other, to make it higher pitched than balls hitting walls
+const BALL_HIT_SOUND_SPEED: f32 = 1.6;
+/// How much quieter sounds get when they're as far away from the nearest player as they can be
+const MAX_DISTANCE_ATTENUATION: f32 = 0.3;
+/// How far apart the listener's ears are. Sounds are never placed further than this from either ear, so the only falloff with distance is what's applied to the volume directly.
+const EAR_GAP: f32 = 1.0;
+/// Spatial sounds are played at three quarters of their volume in each ear when they're centered, so they need to be louder to match how loud sounds used to be
+const SPATIAL_VOLUME_COMPENSATION: f32 = 4.0 / 3.0;
+
+/// How loud the hit sound is when a ball hits a side with an effect, relative to other hits
+const SPECIAL_SIDE_HIT_VOLUME: f32 = 0.75;
+
+pub struct SoundsPlugin;
+
+impl Plugin for SoundsPlugin {
+    fn build(&self, app: &mut App) {
+        app.add_event::<SoundEvent>()
+            .init_resource::<PlayingVoices>()
+            .add_system(play_sounds.run_if(resource_exists::<AudioAssets>()));
+    }
+}
+
+/// Something that happened during gameplay that should make a sound
+#[derive(Clone, Copy)]
+pub struct SoundEvent {
+    pub kind: SoundKind,
+    /// Where in the arena it happened
+    pub position: Vec2,
+}
+
+impl SoundEvent {
+    pub fn new(kind: SoundKind, position: Vec2) -> Self {
+        SoundEvent { kind, position }
+    }
+}
+
+/// The kinds of things that can happen during gameplay that make sounds
+#[derive(Clone, Copy)]
+pub enum SoundKind {
+    /// A new ball was launched into the arena
+    BallSpawned,
+    /// A ball hit a wall of the arena
+    BallHitWall,
+    /// Two balls hit each other
+    BallHitBall,
+    /// A ball hit a side of a player shape
+    BallHitSide(SideType),
+    /// A ball hit an obstacle
+    BallHitObstacle(ObstacleSound),
+    /// A ball went into a score area
+    BallScored {
+        /// Whether the ball matched the score area
+        correct: bool,
+    },
+    /// The effect of a side was applied to a ball
+    SideEffect(SideType),
+    /// A ball went through a portal
+    BallTeleported,
+}
+
+/// A group of sounds that share a volume. Earlier categories are more important, so their sounds get played first when there are too many at once.
+#[derive(Clone, Copy, PartialEq, Eq, PartialOrd, Ord)]
+enum SoundCategory {
+    Scores,
+    Effects,
+    Hits,
+}
+
+impl SoundCategory {
+    /// Gets the volume that all sounds in this category are played at before their own volume is applied
+    fn volume(&self) -> f32 {
+        match self {
+            SoundCategory::Scores => SCORES_VOLUME,
+            SoundCategory::Effects => EFFECTS_VOLUME,
+            SoundCategory::Hits => HITS_VOLUME,
+        }
+    }
+}
+
+/// How to play the sound for an event
+struct Sound<'a> {
+    category: SoundCategory,
+    /// The audio to choose from each time the sound is played
+    variants: &'a [Handle<AudioSource>],
+    /// The volume relative to the category's volume
+    volume: f32,
+    speed: f32,
+}
+
+impl<'a> Sound<'a> {
+    fn new(category: SoundCategory, variants: &'a [Handle<AudioSource>], volume: f32) -> Self {
+        Sound {
+            category,
+            variants,
+            volume,
+            speed: 1.0,
+        }
+    }
+
+    fn single(category: SoundCategory, audio: &'a Handle<AudioSource>, volume: f32) -> Self {
+        Sound::new(category, slice::from_ref(audio), volume)
+    }
+}
+
+impl SoundKind {
+    /// Gets the sound to play for this kind of event, if it makes one
+    fn sound<'a>(&self, audio_assets: &'a AudioAssets) -> Option<Sound<'a>> {
+        let sound = match self {
+            SoundKind::BallSpawned => {
+                Sound::single(SoundCategory::Effects, &audio_assets.launch, 0.8)
+            }
+            SoundKind::BallHitWall | SoundKind::BallHitSide(SideType::NothingSpecial) => {
+                Sound::single(SoundCategory::Hits, &audio_assets.hit, 1.0)
+            }
+            // the side's effect makes its own sound, so the hit doesn't need to be as loud
+            SoundKind::BallHitSide(_) => Sound::single(
+                SoundCategory::Hits,
+                &audio_assets.hit,
+                SPECIAL_SIDE_HIT_VOLUME,
+            ),
+            SoundKind::BallHitBall => Sound {
+                speed: BALL_HIT_SOUND_SPEED,
+                ..Sound::single(SoundCategory::Hits, &audio_assets.hit, 0.625)
+            },
+            SoundKind::BallHitObstacle(ObstacleSound::Hit) => {
+                Sound::single(SoundCategory::Hits, &audio_assets.hit, 1.0)
+            }
+            SoundKind::BallHitObstacle(ObstacleSound::Boop) => {
+                Sound::single(SoundCategory::Hits, &audio_assets.boop, 1.0)
+            }
+            SoundKind::BallScored { correct: true } => {
+                Sound::new(SoundCategory::Scores, &audio_assets.good, 0.825)
+            }
+            SoundKind::BallScored { correct: false } => {
+                Sound::single(SoundCategory::Scores, &audio_assets.bad, 1.0)
+            }
+            SoundKind::SideEffect(side_type) => match side_type {
+                SideType::NothingSpecial => return None,
+                SideType::SpeedUp => Sound::single(SoundCategory::Effects, &audio_assets.up, 1.5),
+                SideType::FreezeOthers => {
+                    Sound::single(SoundCategory::Effects, &audio_assets.down, 2.0)
+                }
+                SideType::BounceBackwards => {
+                    Sound::single(SoundCategory::Effects, &audio_assets.boop, 0.66)
+                }
+                SideType::Destroy => {
+                    Sound::single(SoundCategory::Effects, &audio_assets.explode, 0.66)
+                }
+                SideType::Duplicate => {
+                    Sound::new(SoundCategory::Effects, &audio_assets.duplicate, 0.8)
+                }
+                SideType::ResizeScoreAreas => {
+                    Sound::single(SoundCategory::Effects, &audio_assets.resize, 0.66)
+                }
+                SideType::ExtremeBounce => {
+                    Sound::new(SoundCategory::Effects, &audio_assets.up_more, 0.66)
+                }
+                SideType::ExtraPoints => {
+                    Sound::single(SoundCategory::Effects, &audio_assets.extra_points, 1.32)
+                }
+            },
+            SoundKind::BallTeleported => {
+                Sound::single(SoundCategory::Effects, &audio_assets.launch, 0.66)
+            }
+        };
+
+        Some(sound)
+    }
+}
+
+/// When each sound effect that's still playing started
+#[derive(Resource, Default)]
+struct PlayingVoices(VecDeque<Duration>);
+
+/// Plays the sounds for sound events, skipping the least important ones if too many are already playing
+fn play_sounds(
+    mut sound_events: EventReader<SoundEvent>,
+    mut playing_voices: ResMut<PlayingVoices>,
+    audio: Res<Audio>,
+    audio_settings: Res<AudioSettings>,
+    audio_assets: Res<AudioAssets>,
+    players_query: Query<&Transform, With<PlayerShape>>,
+    time: Res<Time>,
+) {
+    let now = time.elapsed();
+    while playing_voices
+        .0
+        .front()
+        .is_some_and(|started| now.saturating_sub(*started) >= VOICE_DURATION)
+    {
+        playing_voices.0.pop_front();
+    }
+
+    let mut sounds = sound_events
+        .iter()
+        .filter_map(|event| Some((event.kind.sound(&audio_assets)?, event.position)))
+        .collect::<Vec<_>>();
+    sounds.sort_by_key(|(sound, _)| sound.category);
+
+    // rodio plays spatial sounds louder in the ear that's further away from them, so the listener faces away from the screen to put them on the correct side
+    let listener = Transform::from_rotation(Quat::from_rotation_y(PI));
+
+    let mut rng = thread_rng();
+    for (sound, position) in sounds {
+        if playing_voices.0.len() >= MAX_VOICES {
+            break;
+        }
+
+        let audio_source = sound
+            .variants
+            .choose(&mut rng)
+            .expect("sounds should have at least one variant")
+            .clone();
+        let speed = sound.speed * rng.gen_range(1.0 - PITCH_VARIATION..=1.0 + PITCH_VARIATION);
+        let volume = sound.category.volume()
+            * sound.volume
+            * distance_attenuation(position, &players_query)
+            * SPATIAL_VOLUME_COMPENSATION;
+        let pan = (position.x / PLAY_AREA_RADIUS).clamp(-1.0, 1.0);
+        audio.play_spatial_with_settings(
+            audio_source,
+            PlaybackSettings::ONCE
+                .with_volume(audio_settings.effects_volume(volume))
+                .with_speed(speed),
+            listener,
+            EAR_GAP,
+            Vec3::new(pan * EAR_GAP / 2.0, 0.0, 0.0),
+        );
+        playing_voices.0.push_back(now);
+    }
+}
+
+/// Gets how much to scale the volume of a sound at the provided position by, based on how far it is from the nearest player
+fn distance_attenuation(
+    position: Vec2,
+    players_query: &Query<&Transform, With<PlayerShape>>,
+) -> f32 {
+    let nearest_distance = players_query
+        .iter()
+        .map(|transform| transform.translation.truncate().distance(position))
+        .reduce(f32::min)
+        .unwrap_or(0.0);
+
+    1.0 - MAX_DISTANCE_ATTENUATION * (nearest_distance / (PLAY_AREA_RADIUS * 2.0)).min(1.0)
+}
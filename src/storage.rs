@@ -0,0 +1,83 @@
+//! Saving and loading data that should stick around between play sessions
+
+use serde::{de::DeserializeOwned, Serialize};
+
+use crate::*;
+
+#[cfg(not(target_arch = "wasm32"))]
+const SAVE_DIRECTORY_NAME: &str = "extreme-bounce-party-2000";
+
+#[cfg(target_arch = "wasm32")]
+const LOCAL_STORAGE_PREFIX: &str = "extreme-bounce-party-2000.";
+
+/// Loads the data saved under the provided key, if there is any
+pub fn load<T: DeserializeOwned>(key: &str) -> Option<T> {
+    match ron::from_str(&read(key)?) {
+        Ok(data) => Some(data),
+        Err(e) => {
+            warn!("unable to load saved {key}: {e}");
+            None
+        }
+    }
+}
+
+/// Saves the provided data under the provided key, replacing anything already saved under that key
+pub fn save<T: Serialize>(key: &str, data: &T) {
+    match ron::to_string(data) {
+        Ok(serialized) => write(key, &serialized),
+        Err(e) => warn!("unable to save {key}: {e}"),
+    }
+}
+
+#[cfg(not(target_arch = "wasm32"))]
+fn read(key: &str) -> Option<String> {
+    std::fs::read_to_string(save_path(key)?).ok()
+}
+
+#[cfg(not(target_arch = "wasm32"))]
+fn write(key: &str, contents: &str) {
+    let Some(path) = save_path(key) else {
+        warn!("unable to save {key}: no data directory");
+        return;
+    };
+
+    let result = path
+        .parent()
+        .map_or(Ok(()), std::fs::create_dir_all)
+        .and_then(|_| std::fs::write(&path, contents));
+    if let Err(e) = result {
+        warn!("unable to save {key}: {e}");
+    }
+}
+
+/// Gets the path of the file to save data under the provided key in
+#[cfg(not(target_arch = "wasm32"))]
+fn save_path(key: &str) -> Option<std::path::PathBuf> {
+    Some(
+        dirs::data_dir()?
+            .join(SAVE_DIRECTORY_NAME)
+            .join(format!("{key}.ron")),
+    )
+}
+
+#[cfg(target_arch = "wasm32")]
+fn read(key: &str) -> Option<String> {
+    local_storage()?
+        .get_item(&format!("{LOCAL_STORAGE_PREFIX}{key}"))
+        .ok()?
+}
+
+#[cfg(target_arch = "wasm32")]
+fn write(key: &str, contents: &str) {
+    let saved = local_storage()
+        .map(|storage| storage.set_item(&format!("{LOCAL_STORAGE_PREFIX}{key}"), contents))
+        .is_some_and(|result| result.is_ok());
+    if !saved {
+        warn!("unable to save {key} to local storage");
+    }
+}
+
+#[cfg(target_arch = "wasm32")]
+fn local_storage() -> Option<web_sys::Storage> {
+    web_sys::window()?.local_storage().ok()?
+}
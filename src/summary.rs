@@ -0,0 +1,193 @@
+use crate::*;
+
+pub struct SummaryPlugin;
+
+impl Plugin for SummaryPlugin {
+    fn build(&self, app: &mut App) {
+        app.add_system(summary_setup.in_schedule(OnEnter(GameState::Summary)))
+            .add_system(
+                despawn_components_system::<SummaryComponent>
+                    .in_schedule(OnExit(GameState::Summary)),
+            )
+            .add_system(play_again_button_system.run_if(in_state(GameState::Summary)))
+            .add_system(back_to_menu_button_system.run_if(in_state(GameState::Summary)));
+    }
+}
+
+#[derive(Component)]
+struct SummaryComponent;
+
+#[derive(Component)]
+struct PlayAgainButton;
+
+#[derive(Component)]
+struct BackToMenuButton;
+
+/// Sets up the summary screen shown at the end of an endless run, daily challenge, or versus match
+#[allow(clippy::too_many_arguments)]
+fn summary_setup(
+    mut commands: Commands,
+    asset_server: Res<AssetServer>,
+    score: Res<Score>,
+    game_mode: Res<GameMode>,
+    time_survived: Option<Res<TimeSurvived>>,
+    daily_challenge: Option<Res<DailyChallenge>>,
+    daily_challenge_scores: Res<DailyChallengeScores>,
+    versus_scores: Res<VersusScores>,
+) {
+    let (title, lines, play_again_text) = match (*game_mode, daily_challenge) {
+        (GameMode::Versus, _) => (
+            match versus_scores.winner() {
+                Some(winner) => format!("{} wins!", winner.name()),
+                None => "it's a tie!".to_string(),
+            },
+            [Player::One, Player::Two]
+                .into_iter()
+                .map(|player| format!("{} got {} points", player.name(), versus_scores.get(player)))
+                .collect(),
+            "rematch",
+        ),
+        (GameMode::DailyChallenge, Some(daily_challenge)) => {
+            let recorded_text = if daily_challenge.practice {
+                let recorded_score = daily_challenge_scores
+                    .0
+                    .get(&daily_challenge.day)
+                    .copied()
+                    .unwrap_or_default();
+                format!("this was practice, your scored attempt got {recorded_score} points")
+            } else {
+                "your score for today has been recorded".to_string()
+            };
+
+            (
+                format!("daily challenge {}", daily_challenge.date()),
+                vec![format!("you got {} points", score.0), recorded_text],
+                "practice",
+            )
+        }
+        _ => (
+            "out of lives".to_string(),
+            vec![
+                format!(
+                    "you survived for {}",
+                    format_time(time_survived.map(|time| time.0).unwrap_or_default())
+                ),
+                format!("and got {} points", score.0),
+            ],
+            "play again",
+        ),
+    };
+
+    commands
+        .spawn(NodeBundle {
+            style: Style {
+                size: Size::new(Val::Percent(100.0), Val::Percent(100.0)),
+                flex_direction: FlexDirection::Column,
+                justify_content: JustifyContent::Center,
+                align_items: AlignItems::Center,
+                ..default()
+            },
+            ..default()
+        })
+        .insert(SummaryComponent)
+        .with_children(|parent| {
+            parent.spawn(
+                TextBundle::from_section(
+                    title,
+                    TextStyle {
+                        font: asset_server.load(TITLE_FONT),
+                        font_size: 75.0,
+                        color: Color::rgb(0.0, 0.9, 1.0),
+                    },
+                )
+                .with_text_alignment(TextAlignment::Center)
+                .with_style(Style {
+                    margin: UiRect {
+                        bottom: Val::Px(20.0),
+                        ..default()
+                    },
+                    ..default()
+                }),
+            );
+
+            let line_count = lines.len();
+            for (i, line) in lines.into_iter().enumerate() {
+                let bottom_margin = if i == line_count - 1 { 40.0 } else { 10.0 };
+                parent.spawn(
+                    TextBundle::from_section(
+                        line,
+                        TextStyle {
+                            font: asset_server.load(MONO_FONT),
+                            font_size: 45.0,
+                            color: Color::WHITE,
+                        },
+                    )
+                    .with_text_alignment(TextAlignment::Center)
+                    .with_style(Style {
+                        margin: UiRect {
+                            bottom: Val::Px(bottom_margin),
+                            ..default()
+                        },
+                        max_size: Size {
+                            width: Val::Px(WINDOW_WIDTH * 0.8),
+                            ..default()
+                        },
+                        ..default()
+                    }),
+                );
+            }
+
+            // buttons
+            parent
+                .spawn(NodeBundle {
+                    style: Style {
+                        size: Size::new(Val::Percent(100.0), Val::Auto),
+                        flex_direction: FlexDirection::Row,
+                        justify_content: JustifyContent::Center,
+                        align_items: AlignItems::Center,
+                        ..default()
+                    },
+                    ..default()
+                })
+                .with_children(|parent| {
+                    spawn_menu_button(parent, &asset_server, play_again_text)
+                        .insert(PlayAgainButton);
+                    spawn_menu_button(parent, &asset_server, "back to menu")
+                        .insert(BackToMenuButton);
+                });
+        });
+}
+
+type InteractedPlayAgainButtonTuple = (Changed<Interaction>, With<PlayAgainButton>);
+
+/// Handles interactions with the play again button.
+fn play_again_button_system(
+    mut next_state: ResMut<NextState<GameState>>,
+    mut level_settings: ResMut<LevelSettings>,
+    game_mode: Res<GameMode>,
+    interaction_query: Query<&Interaction, InteractedPlayAgainButtonTuple>,
+) {
+    for interaction in interaction_query.iter() {
+        if *interaction == Interaction::Clicked {
+            if *game_mode == GameMode::Endless {
+                // endless mode changes the level settings as it gets harder, so they need to be reset
+                *level_settings = LevelSettings::endless();
+            }
+            next_state.set(GameState::Game);
+        }
+    }
+}
+
+type InteractedBackToMenuButtonTuple = (Changed<Interaction>, With<BackToMenuButton>);
+
+/// Handles interactions with the back to menu button.
+fn back_to_menu_button_system(
+    mut next_state: ResMut<NextState<GameState>>,
+    interaction_query: Query<&Interaction, InteractedBackToMenuButtonTuple>,
+) {
+    for interaction in interaction_query.iter() {
+        if *interaction == Interaction::Clicked {
+            next_state.set(GameState::Menu);
+        }
+    }
+}